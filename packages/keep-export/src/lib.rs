@@ -81,7 +81,7 @@ impl Analyzer<'_> {
             return e
         }
 
-        return e;
+        e
     }
 }
 
@@ -147,7 +147,7 @@ impl Fold for Analyzer<'_> {
 
         self.in_kept_fn = old_in_kept;
 
-        return e;
+        e
     }
 
     fn fold_expr(&mut self, e: Expr) -> Expr {
@@ -276,17 +276,15 @@ impl Fold for Analyzer<'_> {
         }
 
         // Visit children to ensure that all references is added to the scope.
-        let s = s.fold_children_with(self);
-
-        s
+        s.fold_children_with(self)
     }
 
     fn fold_default_decl(&mut self, d: DefaultDecl) -> DefaultDecl {
-        return self.check_default(d);
+        self.check_default(d)
     }
 
     fn fold_export_default_expr(&mut self, e: ExportDefaultExpr) -> ExportDefaultExpr {
-        return self.check_default(e);
+        self.check_default(e)
     }
 
     fn fold_prop(&mut self, p: Prop) -> Prop {
@@ -415,7 +413,7 @@ impl Fold for KeepExportsExprs {
         items.retain(|s| !matches!(s, ModuleItem::Stmt(Stmt::Empty(..))));
 
         // If all exports are deleted, return the empty named export.
-        if items.len() == 0 {
+        if items.is_empty() {
             items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport{
                 span: DUMMY_SP,
                 specifiers:  Vec::new(),
@@ -1,26 +1,56 @@
+use fxhash::FxHashSet;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
-use swc_common::{SyntaxContext, DUMMY_SP};
+use swc_common::{
+    comments::{Comments, NoopComments},
+    errors::HANDLER,
+    plugin::metadata::TransformPluginMetadataContextKind,
+    BytePos, Mark, Span, Spanned, SyntaxContext, DUMMY_SP,
+};
 use swc_core::ecma::{
     ast::*,
     atoms::JsWord,
-    visit::{Fold, FoldWith},
+    utils::{collect_decls, find_pat_ids},
+    visit::{noop_visit_type, Fold, FoldWith, Visit, VisitWith},
 };
 use swc_plugin_proxy::TransformPluginProgramMetadata;
 use swc_plugin_macro::plugin_transform;
 
+/// Experimental plugin context key which the host uses to provide the platform, such as `web`.
+pub const PLATFORM_CONTEXT_KEY: &str = "platform";
+
 #[derive(Debug, Deserialize, Default, Clone)]
 pub struct KeepPlatformPatcher {
-    pub platform: String,
+    /// Module will be left untouched when disabled.
+    pub enabled: bool,
+    pub platforms: Vec<String>,
+    pub unknown_flags: UnknownFlags,
+    pub define: HashMap<String, Value>,
+    pub jsx_platform_attribute: Option<String>,
+    pub strict: bool,
+    /// Identifiers referenced by removed JSX elements.
+    #[serde(skip)]
+    removed_jsx_refs: FxHashSet<Id>,
+    /// Bindings declared in the module, which shadow defined globals.
+    #[serde(skip)]
+    declared_ids: FxHashSet<Id>,
+    /// Namespace objects of env sources, such as `env` of `import * as env from 'universal-env'`.
+    #[serde(skip)]
+    env_namespaces: FxHashSet<Id>,
 }
 
 /// Configuration related to source map generated by swc.
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum KeepPlatformConfig {
+    /// `false` leaves the module untouched, `true` uses the platform provided by
+    /// the host through plugin context key [PLATFORM_CONTEXT_KEY].
     Bool(bool),
     KeepPlatform(String),
+    KeepPlatforms(Vec<String>),
+    Options(KeepPlatformOptions),
 }
 
 impl Default for KeepPlatformConfig {
@@ -29,12 +59,109 @@ impl Default for KeepPlatformConfig {
     }
 }
 
+/// Options for building one bundle that targets several platforms, such as `web` + `kraken`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepPlatformOptions {
+    pub platforms: Vec<String>,
+    #[serde(default)]
+    pub unknown_flags: UnknownFlags,
+    /// Replace expressions with literal values at compile time, such as
+    /// `{ "process.env.PLATFORM": "web", "typeof window": "object", "__IS_SERVER__": false }`.
+    #[serde(default)]
+    pub define: HashMap<String, Value>,
+    /// Remove JSX elements whose attribute does not match the platforms, such as
+    /// `<View platform="weex" />` with `"platform"`. Comma separated values are supported.
+    #[serde(default)]
+    pub jsx_platform_attribute: Option<String>,
+    /// Treat platform suffix of filename as file platform, such as `Foo.weex.tsx`.
+    #[serde(default)]
+    pub file_suffix: bool,
+    #[serde(default)]
+    pub file_mismatch: FileMismatch,
+    /// Report unknown platforms and flags as errors instead of warnings.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// How to transform module whose file platform does not match the platforms.
+/// File platform is declared by `/* @platform weex */` or filename suffix.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileMismatch {
+    /// Replace module with an empty module.
+    #[default]
+    Empty,
+    /// Replace module with `undefined` stubs for its exports.
+    Stub,
+}
+
+/// Host provided information of the module being transformed.
+#[derive(Debug, Default, Clone)]
+pub struct KeepPlatformContext {
+    /// Platform used by `KeepPlatformConfig::Bool(true)`.
+    pub platform: Option<String>,
+    /// Filename of the module, such as `Foo.weex.tsx`.
+    pub filename: Option<String>,
+}
+
+/// How to handle env flags which are not defined by any platform, such as `isAndroid`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnknownFlags {
+    /// Replace unknown flags with `false`.
+    #[default]
+    Fold,
+    /// Keep unknown flags as runtime values imported from the env source.
+    Dynamic,
+}
+
 pub fn keep_platform(options: KeepPlatformConfig) -> impl Fold {
-    let platform: String = match options {
-        KeepPlatformConfig::KeepPlatform(platform) => platform,
-        _ => "".to_string(),
+    keep_platform_with_context(options, Default::default(), None::<NoopComments>)
+}
+
+/// Same as [keep_platform], with host provided context and comments of the module
+/// which are used to find file-level platform pragma.
+pub fn keep_platform_with_context<C: Comments>(
+    options: KeepPlatformConfig,
+    context: KeepPlatformContext,
+    comments: Option<C>,
+) -> impl Fold {
+    let options = match options {
+        KeepPlatformConfig::KeepPlatform(platform) => KeepPlatformOptions {
+            platforms: vec![platform],
+            ..Default::default()
+        },
+        KeepPlatformConfig::KeepPlatforms(platforms) => KeepPlatformOptions {
+            platforms,
+            ..Default::default()
+        },
+        KeepPlatformConfig::Options(options) => options,
+        KeepPlatformConfig::Bool(true) => match context.platform {
+            Some(platform) if !platform.is_empty() => KeepPlatformOptions {
+                platforms: vec![platform],
+                ..Default::default()
+            },
+            // Do not guess the platform, otherwise every platform branch will be removed.
+            _ => return FilePlatformPatcher::disabled(comments),
+        },
+        KeepPlatformConfig::Bool(false) => return FilePlatformPatcher::disabled(comments),
     };
-    KeepPlatformPatcher { platform: platform }
+    FilePlatformPatcher {
+        patcher: KeepPlatformPatcher {
+            enabled: true,
+            platforms: options.platforms,
+            unknown_flags: options.unknown_flags,
+            define: options.define,
+            jsx_platform_attribute: options.jsx_platform_attribute,
+            strict: options.strict,
+            ..Default::default()
+        },
+        comments,
+        filename: context.filename,
+        file_suffix: options.file_suffix,
+        file_mismatch: options.file_mismatch,
+    }
 }
 
 // Define platform maps.
@@ -70,88 +197,720 @@ lazy_static! {
     ]);
 }
 
+impl KeepPlatformPatcher {
+    // Get flags of all selected platforms, such as ["isKraken", "isWeb"] for web + kraken.
+    fn platform_flags(&self) -> Vec<String> {
+        let mut platform_flags: Vec<String> = vec![];
+        for platform in self.platforms.iter() {
+            if let Some(flags) = PLATFORM_MAP.get(platform) {
+                for flag in flags {
+                    if !platform_flags.contains(flag) {
+                        platform_flags.push(flag.to_string());
+                    }
+                }
+            }
+        }
+        platform_flags
+    }
+
+    // Report platforms which are not defined by platform maps.
+    fn check_platforms(&self) {
+        for platform in self.platforms.iter() {
+            if !PLATFORM_MAP.contains_key(platform) {
+                self.report(
+                    DUMMY_SP,
+                    &format!("keep-platform: unknown platform `{}`, all platform flags will be `false`", platform),
+                    find_similar_name(platform, PLATFORM_MAP.keys()),
+                );
+            }
+        }
+    }
+
+    // Report flags read from env namespace objects, such as `env.isWechatMiniProgram`.
+    fn check_namespace_member(&self, member: &MemberExpr) {
+        let Expr::Ident(obj) = &*member.obj else {
+            return;
+        };
+        if !self.env_namespaces.contains(&obj.to_id()) {
+            return;
+        }
+        let flag = match &member.prop {
+            MemberProp::Ident(prop) => &prop.sym,
+            MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                Expr::Lit(Lit::Str(str)) => &str.value,
+                _ => return,
+            },
+            _ => return,
+        };
+        // The folded namespace object only holds the true flags, so unknown flags read `undefined`.
+        if !is_known_flag(flag) && !self.is_dynamic_flag(flag) {
+            self.report(
+                member.prop.span(),
+                &format!("keep-platform: unknown platform flag `{}` of env namespace object is `undefined`", flag),
+                find_similar_name(flag, known_flags().iter()),
+            );
+        }
+    }
+
+    // Report flags which are not defined by any platform, such as `isWechatMiniProgram`.
+    fn check_flag(&self, flag: &str, span: Span) {
+        if !is_known_flag(flag) {
+            let known_flags = known_flags();
+            self.report(
+                span,
+                &format!("keep-platform: unknown platform flag `{}` is replaced with `false`", flag),
+                find_similar_name(flag, known_flags.iter()),
+            );
+        }
+    }
+
+    fn report(&self, span: Span, msg: &str, suggestion: Option<String>) {
+        HANDLER.with(|handler| {
+            let mut diagnostic = match (self.strict, span.is_dummy()) {
+                (true, true) => handler.struct_err(msg),
+                (true, false) => handler.struct_span_err(span, msg),
+                (false, true) => handler.struct_warn(msg),
+                (false, false) => handler.struct_span_warn(span, msg),
+            };
+            if let Some(suggestion) = suggestion {
+                diagnostic.help(&format!("did you mean `{}`?", suggestion));
+            }
+            diagnostic.emit();
+        });
+    }
+
+    // Whether the flag should be kept as a runtime value instead of being folded.
+    fn is_dynamic_flag(&self, flag: &str) -> bool {
+        self.unknown_flags == UnknownFlags::Dynamic && !is_known_flag(flag)
+    }
+
+    // Create object by platform flags, such as { isWeb: true }
+    fn create_flags_object(
+        &self,
+        platform_flags: &[String],
+        runtime_env: Option<Box<Expr>>,
+        span: Span,
+    ) -> Expr {
+        let mut props: Vec<PropOrSpread> = vec![];
+        let mut flags: Vec<String> = platform_flags.to_vec();
+        if let Some(runtime_env) = runtime_env {
+            // Spread runtime env first, so known flags always override runtime values.
+            props.push(PropOrSpread::Spread(SpreadElement {
+                dot3_token: DUMMY_SP,
+                expr: runtime_env,
+            }));
+            flags = known_flags();
+        }
+        props.extend(flags.iter().map(|flag| {
+            PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(create_jsword_ident(flag)),
+                value: Box::new(create_bool_expr(platform_flags.contains(flag), span)),
+            })))
+        }));
+        Expr::Object(ObjectLit {
+            span,
+            props,
+        })
+    }
+
+    // Get defined value of expression, such as `process.env.PLATFORM` or `typeof window`.
+    fn get_define_value(&self, expr: &Expr) -> Option<Expr> {
+        if self.define.is_empty() {
+            return None;
+        }
+        let (key, root) = match expr {
+            Expr::Unary(UnaryExpr {
+                op: op!("typeof"),
+                arg,
+                ..
+            }) => (format!("typeof {}", get_expr_path(arg)?), get_expr_root(arg)?),
+            _ => (get_expr_path(expr)?, get_expr_root(expr)?),
+        };
+        // Locals like `function f(process) {}` are not the defined globals.
+        if self.declared_ids.contains(&root.to_id()) {
+            return None;
+        }
+        self.define.get(&key).map(|value| create_value_expr(value, expr.span()))
+    }
+
+    // Whether the JSX element is rendered only on other platforms, such as <View platform="weex" />.
+    fn should_remove_jsx_element(&self, jsx_element: &JSXElement) -> bool {
+        let Some(attribute) = &self.jsx_platform_attribute else {
+            return false;
+        };
+        jsx_element.opening.attrs.iter().any(|attr| {
+            get_jsx_attr_value(attr, attribute).is_some_and(|value| {
+                !value
+                    .split(',')
+                    .any(|platform| self.platforms.iter().any(|p| p == platform.trim()))
+            })
+        })
+    }
+
+    fn collect_removed_jsx_refs(&mut self, jsx_element: &JSXElement) {
+        let mut collector = IdentCollector::default();
+        jsx_element.visit_with(&mut collector);
+        self.removed_jsx_refs.extend(collector.ids);
+    }
+
+    // Remove import specifiers which are only referenced by removed JSX elements.
+    fn remove_unused_imports(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        if self.removed_jsx_refs.is_empty() {
+            return items;
+        }
+        let mut collector = IdentCollector::default();
+        for item in items.iter() {
+            if !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))) {
+                item.visit_with(&mut collector);
+            }
+        }
+        let removed_jsx_refs = std::mem::take(&mut self.removed_jsx_refs);
+        let is_unused = |local: &Ident| {
+            let id = local.to_id();
+            removed_jsx_refs.contains(&id) && !collector.ids.contains(&id)
+        };
+        items
+            .into_iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(mut import_decl)) => {
+                    if import_decl.specifiers.is_empty() {
+                        return Some(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)));
+                    }
+                    import_decl.specifiers.retain(|specifier| match specifier {
+                        ImportSpecifier::Named(named) => !is_unused(&named.local),
+                        ImportSpecifier::Default(default) => !is_unused(&default.local),
+                        ImportSpecifier::Namespace(namespace) => !is_unused(&namespace.local),
+                    });
+                    if import_decl.specifiers.is_empty() {
+                        None
+                    } else {
+                        Some(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)))
+                    }
+                }
+                _ => Some(item),
+            })
+            .collect()
+    }
+
+    // Replace dynamic import of env source, such as:
+    // import('universal-env') -> Promise.resolve({ isWeb: true })
+    // import('universal-env') -> import('universal-env').then((env) => ({ ...env, isWeb: true, ... }))
+    fn fold_env_dynamic_import(&self, expr: &Expr) -> Option<Expr> {
+        let Expr::Call(call_expr @ CallExpr {
+            callee: Callee::Import(_),
+            args,
+            span,
+            ..
+        }) = expr
+        else {
+            return None;
+        };
+        match args.first().map(|arg| &*arg.expr) {
+            Some(Expr::Lit(Lit::Str(str))) if check_source(&str.value) => {}
+            _ => return None,
+        }
+        let platform_flags = self.platform_flags();
+        if self.unknown_flags == UnknownFlags::Dynamic {
+            let runtime_env = Ident::new(JsWord::from("env"), *span, SyntaxContext::empty().apply_mark(Mark::new()));
+            let flags_object = self.create_flags_object(
+                &platform_flags,
+                Some(Box::new(Expr::Ident(runtime_env.clone()))),
+                *span,
+            );
+            return Some(create_method_call(
+                Expr::Call(call_expr.clone()),
+                "then",
+                Expr::Arrow(ArrowExpr {
+                    span: *span,
+                    params: vec![Pat::Ident(BindingIdent {
+                        id: runtime_env,
+                        type_ann: None,
+                    })],
+                    body: Box::new(BlockStmtOrExpr::Expr(Box::new(Expr::Paren(ParenExpr {
+                        span: *span,
+                        expr: Box::new(flags_object),
+                    })))),
+                    ..Default::default()
+                }),
+                *span,
+            ));
+        }
+        Some(create_method_call(
+            Expr::Ident(Ident::new(JsWord::from("Promise"), *span, SyntaxContext::empty())),
+            "resolve",
+            self.create_flags_object(&platform_flags, None, *span),
+            *span,
+        ))
+    }
+
+    // Replace declarators which require env source in place, such as:
+    // const { isWeb } = require('universal-env') -> const isWeb = true
+    // const env = require('universal-env') -> const env = { isWeb: true }
+    fn fold_require_decl(&mut self, var_decl: &VarDecl, platform_flags: &[String]) -> Option<VarDecl> {
+        let mut decls: Vec<VarDeclarator> = vec![];
+        for decl in var_decl.decls.iter() {
+            let Some(require_call) = decl.init.as_deref().filter(|init| is_env_require(init)) else {
+                decls.push(decl.clone());
+                continue;
+            };
+            match &decl.name {
+                Pat::Ident(binding) => {
+                    self.env_namespaces.insert(binding.id.to_id());
+                    let runtime_env = if self.unknown_flags == UnknownFlags::Dynamic {
+                        Some(Box::new(require_call.clone()))
+                    } else {
+                        None
+                    };
+                    decls.push(create_var_decl(
+                        binding.id.clone(),
+                        Option::Some(Box::new(self.create_flags_object(
+                            platform_flags,
+                            runtime_env,
+                            decl.span,
+                        ))),
+                        decl.span,
+                    ));
+                }
+                Pat::Object(object_pat)
+                    if !object_pat.props.iter().any(|prop| matches!(prop, ObjectPatProp::Rest(_))) =>
+                {
+                    // Props which are resolved at runtime
+                    let mut runtime_props: Vec<ObjectPatProp> = vec![];
+                    let mut env_decls: Vec<VarDeclarator> = vec![];
+                    for prop in object_pat.props.iter() {
+                        let env_variable = match prop {
+                            ObjectPatProp::Assign(AssignPatProp { key, .. }) => {
+                                Some((key.sym.clone(), key.id.clone()))
+                            }
+                            ObjectPatProp::KeyValue(KeyValuePatProp { key, value }) => {
+                                match (key, &**value) {
+                                    (PropName::Ident(key), Pat::Ident(binding)) => {
+                                        Some((key.sym.clone(), binding.id.clone()))
+                                    }
+                                    (PropName::Str(key), Pat::Ident(binding)) => {
+                                        Some((key.value.clone(), binding.id.clone()))
+                                    }
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        };
+                        match env_variable {
+                            Some((flag, local)) if !self.is_dynamic_flag(&flag) => {
+                                self.check_flag(&flag, prop.span());
+                                env_decls.push(create_var_decl(
+                                    local,
+                                    Option::Some(Box::new(create_bool_expr(
+                                        platform_flags.contains(&flag.to_string()),
+                                        prop.span(),
+                                    ))),
+                                    prop.span(),
+                                ));
+                            }
+                            _ => runtime_props.push(prop.clone()),
+                        }
+                    }
+                    if !runtime_props.is_empty() {
+                        decls.push(VarDeclarator {
+                            name: Pat::Object(ObjectPat {
+                                props: runtime_props,
+                                ..object_pat.clone()
+                            }),
+                            ..decl.clone()
+                        });
+                    }
+                    decls.extend(env_decls);
+                }
+                _ => decls.push(decl.clone()),
+            }
+        }
+
+        if decls.is_empty() {
+            None
+        } else {
+            Some(VarDecl {
+                decls,
+                ..var_decl.clone()
+            })
+        }
+    }
+
+    // Fold top-level variable declarations which require env source.
+    fn fold_require_stmt(&mut self, stmt: &Stmt, platform_flags: &[String]) -> Option<Stmt> {
+        match stmt {
+            Stmt::Decl(Decl::Var(var_decl)) => self
+                .fold_require_decl(var_decl, platform_flags)
+                .map(|var_decl| Stmt::Decl(Decl::Var(Box::new(var_decl)))),
+            _ => Some(stmt.clone()),
+        }
+    }
+}
+
 impl Fold for KeepPlatformPatcher {
+    fn fold_module(&mut self, module: Module) -> Module {
+        if !self.enabled {
+            return module;
+        }
+        self.check_platforms();
+        if !self.define.is_empty() {
+            self.declared_ids = collect_decls::<Id, _>(&module).into_iter().collect();
+        }
+        module.fold_children_with(self)
+    }
+
+    fn fold_prop(&mut self, prop: Prop) -> Prop {
+        // Expand `{ __IS_SERVER__ }` to `{ __IS_SERVER__: false }`
+        if let Prop::Shorthand(ident) = &prop {
+            if let Some(value) = self.get_define_value(&Expr::Ident(ident.clone())) {
+                return Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(IdentName { span: ident.span, sym: ident.sym.clone() }),
+                    value: Box::new(value),
+                });
+            }
+        }
+        prop.fold_children_with(self)
+    }
+
+    fn fold_script(&mut self, script: Script) -> Script {
+        if !self.enabled {
+            return script;
+        }
+        self.check_platforms();
+        if !self.define.is_empty() {
+            self.declared_ids = collect_decls::<Id, _>(&script).into_iter().collect();
+        }
+        let platform_flags = self.platform_flags();
+        let body = script
+            .body
+            .iter()
+            .filter_map(|stmt| self.fold_require_stmt(stmt, &platform_flags))
+            .collect();
+        Script { body, ..script }.fold_children_with(self)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Some(value) = self.get_define_value(&expr) {
+            return value;
+        }
+        if let Some(value) = self.fold_env_dynamic_import(&expr) {
+            return value;
+        }
+        if let Expr::Member(member) = &expr {
+            self.check_namespace_member(member);
+        }
+        if let Expr::JSXElement(jsx_element) = &expr {
+            if self.should_remove_jsx_element(jsx_element) {
+                self.collect_removed_jsx_refs(jsx_element);
+                return Expr::Lit(Lit::Null(Null { span: jsx_element.span }));
+            }
+        }
+        expr.fold_children_with(self)
+    }
+
+    fn fold_jsx_element_childs(&mut self, children: Vec<JSXElementChild>) -> Vec<JSXElementChild> {
+        children
+            .into_iter()
+            .filter(|child| match child {
+                JSXElementChild::JSXElement(jsx_element) => {
+                    if self.should_remove_jsx_element(jsx_element) {
+                        self.collect_removed_jsx_refs(jsx_element);
+                        false
+                    } else {
+                        true
+                    }
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>()
+            .fold_children_with(self)
+    }
+
+    fn fold_jsx_opening_element(&mut self, opening: JSXOpeningElement) -> JSXOpeningElement {
+        let mut opening = opening.fold_children_with(self);
+        if let Some(attribute) = &self.jsx_platform_attribute {
+            // Strip platform attribute from matched elements.
+            opening.attrs.retain(|attr| get_jsx_attr_value(attr, attribute).is_none());
+        }
+        opening
+    }
+
     fn fold_module_items(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
         // Get platform flag, such as ["isWeb"]
-        let platform_flags: Vec<String> = match PLATFORM_MAP.get(&self.platform.to_string()) {
-            Some(flags) => flags.to_vec(),
-            None => vec![],
-        };
+        let platform_flags = self.platform_flags();
         // Collect top-level expression
         let mut new_module_items: Vec<ModuleItem> = vec![];
-        // Save isWeb/isWeex into env_variables
-        let mut env_variables: Vec<&Ident> = vec![];
-
-        // Decls witch need be inserted into module items
-        let mut decls: Vec<VarDeclarator> = vec![];
+        // Declarations which replace imports, such as `const isWeb = true`
+        let mut import_decls: Vec<ModuleItem> = vec![];
 
         for module_item in items.iter() {
             match module_item {
                 ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
                     if check_source(&import_decl.src.value) {
+                        // Specifiers which are resolved at runtime
+                        let mut runtime_specifiers: Vec<ImportSpecifier> = vec![];
+                        // Decls witch replace the import, such as `const isWeb = true`
+                        let mut decls: Vec<VarDeclarator> = vec![];
                         for specifier in import_decl.specifiers.iter() {
                             match specifier {
                                 ImportSpecifier::Named(named) => {
                                     let ImportNamedSpecifier {
                                         local,
-                                        span: _,
-                                        imported: _,
-                                        is_type_only: _,
+                                        imported,
+                                        span,
+                                        ..
                                     } = named;
-                                    env_variables.push(local);
+                                    let flag = match imported {
+                                        Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                                        Some(ModuleExportName::Str(str)) => &str.value,
+                                        None => &local.sym,
+                                    };
+                                    if self.is_dynamic_flag(flag) {
+                                        runtime_specifiers.push(specifier.clone());
+                                    } else {
+                                        self.check_flag(flag, *span);
+                                        decls.push(create_var_decl(
+                                            local.clone(),
+                                            Option::Some(Box::new(create_bool_expr(
+                                                platform_flags.contains(&flag.to_string()),
+                                                *span,
+                                            ))),
+                                            *span,
+                                        ));
+                                    }
                                 }
                                 ImportSpecifier::Namespace(namespace) => {
-                                    let ImportStarAsSpecifier { local, span: _ } = namespace;
+                                    let ImportStarAsSpecifier { local, span } = namespace;
+                                    self.env_namespaces.insert(local.to_id());
+                                    let runtime_env = if self.unknown_flags == UnknownFlags::Dynamic {
+                                        // import * as env from 'universal-env' -> import * as _env from 'universal-env'
+                                        let runtime_env = Ident::new(
+                                            format!("_{}", local.sym).into(),
+                                            *span,
+                                            SyntaxContext::empty().apply_mark(Mark::new()),
+                                        );
+                                        runtime_specifiers.push(ImportSpecifier::Namespace(
+                                            ImportStarAsSpecifier {
+                                                span: *span,
+                                                local: runtime_env.clone(),
+                                            },
+                                        ));
+                                        Some(Box::new(Expr::Ident(runtime_env)))
+                                    } else {
+                                        None
+                                    };
                                     decls.push(create_var_decl(
                                         local.clone(),
-                                        Option::Some(Box::new(Expr::Object(ObjectLit {
-                                            span: DUMMY_SP,
-                                            // Create object by platform_flags, such as { isWeb: true }
-                                            props: platform_flags
-                                                .iter()
-                                                .map(|platform| {
-                                                    PropOrSpread::Prop(Box::new(Prop::KeyValue(
-                                                        KeyValueProp {
-                                                            key: PropName::Ident(
-                                                                create_jsword_ident(platform),
-                                                            ),
-                                                            value: Box::new(create_bool_expr(true)),
-                                                        },
-                                                    )))
-                                                })
-                                                .collect(),
-                                        }))),
+                                        Option::Some(Box::new(
+                                            self.create_flags_object(&platform_flags, runtime_env, *span),
+                                        )),
+                                        *span,
                                     ))
                                 }
-                                _ => {}
+                                ImportSpecifier::Default(_) => {
+                                    if self.unknown_flags == UnknownFlags::Dynamic {
+                                        runtime_specifiers.push(specifier.clone());
+                                    }
+                                }
                             }
                         }
+                        if !runtime_specifiers.is_empty() {
+                            new_module_items.push(ModuleItem::ModuleDecl(ModuleDecl::Import(
+                                ImportDecl {
+                                    specifiers: runtime_specifiers,
+                                    ..import_decl.clone()
+                                },
+                            )))
+                        }
+                        if !decls.is_empty() {
+                            import_decls.push(ModuleItem::Stmt(create_const_decl(decls, import_decl.span)));
+                        }
                     } else {
                         new_module_items.push(ModuleItem::ModuleDecl(ModuleDecl::Import(
                             import_decl.clone(),
                         )))
                     }
                 }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named))
+                    if !export_named.type_only
+                        && export_named.src.as_ref().is_some_and(|src| check_source(&src.value)) =>
+                {
+                    // Specifiers which are re-exported at runtime
+                    let mut runtime_specifiers: Vec<ExportSpecifier> = vec![];
+                    // Decls witch replace the re-export, such as `export const isWeb = true`
+                    let mut decls: Vec<VarDeclarator> = vec![];
+                    for specifier in export_named.specifiers.iter() {
+                        match specifier {
+                            ExportSpecifier::Named(ExportNamedSpecifier {
+                                orig,
+                                exported,
+                                span,
+                                is_type_only: false,
+                            }) => {
+                                let flag = get_module_export_name(orig);
+                                let export_name = get_module_export_name(exported.as_ref().unwrap_or(orig));
+                                // The default export of env sources is not a flag.
+                                if &*flag == "default" || self.is_dynamic_flag(&flag) {
+                                    runtime_specifiers.push(specifier.clone());
+                                } else if &*export_name == "default" {
+                                    // export { isWeb as default } from 'universal-env' -> export default true
+                                    self.check_flag(&flag, *span);
+                                    let value = platform_flags.contains(&flag.to_string());
+                                    new_module_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+                                        ExportDefaultExpr {
+                                            span: *span,
+                                            expr: Box::new(create_bool_expr(value, *span)),
+                                        },
+                                    )));
+                                } else if !is_valid_binding(&export_name) {
+                                    runtime_specifiers.push(specifier.clone());
+                                } else {
+                                    self.check_flag(&flag, *span);
+                                    decls.push(create_var_decl(
+                                        Ident::new(export_name, *span, SyntaxContext::empty()),
+                                        Option::Some(Box::new(create_bool_expr(
+                                            platform_flags.contains(&flag.to_string()),
+                                            *span,
+                                        ))),
+                                        *span,
+                                    ));
+                                }
+                            }
+                            ExportSpecifier::Namespace(ExportNamespaceSpecifier {
+                                name: ModuleExportName::Ident(name),
+                                span,
+                            }) => {
+                                // export * as env from 'universal-env' -> export const env = { isWeb: true }
+                                let runtime_env = if self.unknown_flags == UnknownFlags::Dynamic {
+                                    let runtime_env = Ident::new(
+                                        format!("_{}", name.sym).into(),
+                                        *span,
+                                        SyntaxContext::empty().apply_mark(Mark::new()),
+                                    );
+                                    new_module_items.push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                                        span: *span,
+                                        specifiers: vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                                            span: *span,
+                                            local: runtime_env.clone(),
+                                        })],
+                                        src: export_named.src.clone().unwrap(),
+                                        type_only: false,
+                                        with: None,
+                                        phase: Default::default(),
+                                    })));
+                                    Some(Box::new(Expr::Ident(runtime_env)))
+                                } else {
+                                    None
+                                };
+                                decls.push(create_var_decl(
+                                    Ident::new(name.sym.clone(), *span, SyntaxContext::empty()),
+                                    Option::Some(Box::new(
+                                        self.create_flags_object(&platform_flags, runtime_env, *span),
+                                    )),
+                                    *span,
+                                ));
+                            }
+                            _ => runtime_specifiers.push(specifier.clone()),
+                        }
+                    }
+                    if !runtime_specifiers.is_empty() {
+                        new_module_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+                            NamedExport {
+                                specifiers: runtime_specifiers,
+                                ..export_named.clone()
+                            },
+                        )))
+                    }
+                    if !decls.is_empty() {
+                        new_module_items.push(create_export_const_decl(decls, export_named.span));
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export_all)) if check_source(&export_all.src.value) => {
+                    // export * from 'universal-env' -> export const isWeb = true, isWeex = false, ...
+                    // Explicit exports take precedence over star exports.
+                    let export_names = collect_export_names(&items);
+                    let decls: Vec<VarDeclarator> = known_flags()
+                        .into_iter()
+                        .filter(|flag| !export_names.iter().any(|name| name == flag))
+                        .map(|flag| {
+                            let value = platform_flags.contains(&flag);
+                            create_var_decl(
+                                Ident::new(JsWord::from(flag), export_all.span, SyntaxContext::empty()),
+                                Option::Some(Box::new(create_bool_expr(value, export_all.span))),
+                                export_all.span,
+                            )
+                        })
+                        .collect();
+                    // Local exports take precedence over star exports, so unknown flags are still re-exported.
+                    if self.unknown_flags == UnknownFlags::Dynamic {
+                        new_module_items.push(module_item.clone());
+                    }
+                    if !decls.is_empty() {
+                        new_module_items.push(create_export_const_decl(decls, export_all.span));
+                    }
+                }
+                ModuleItem::Stmt(stmt) => {
+                    if let Some(stmt) = self.fold_require_stmt(stmt, &platform_flags) {
+                        new_module_items.push(ModuleItem::Stmt(stmt));
+                    }
+                }
                 _ => new_module_items.push(module_item.clone()),
             }
         }
+        // Imports are hoisted, so declarations go after the leading imports instead of where
+        // the import was, otherwise a use before the import would hit the TDZ of `const`.
+        let index = new_module_items
+            .iter()
+            .take_while(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+            .count();
+        new_module_items.splice(index..index, import_decls);
 
-        // If it exist env variables, we need insert declare expression
-        if env_variables.len() > 0 {
-            for env_variable in env_variables {
-                decls.push(create_var_decl(
-                    env_variable.clone(),
-                    Option::Some(Box::new(create_bool_expr(
-                        platform_flags.contains(&env_variable.sym.to_string()),
-                    ))),
-                ));
-            }
-        }
+        let new_module_items = new_module_items.fold_children_with(self);
+        self.remove_unused_imports(new_module_items)
+    }
+}
+
+// Check whether the flag is defined by any platform.
+fn is_known_flag(flag: &str) -> bool {
+    PLATFORM_MAP.values().any(|flags| flags.iter().any(|f| f == flag))
+}
 
-        insert_decls_into_module_items(decls, &mut new_module_items);
+// Get all flags defined by platform maps, sorted to keep output stable.
+fn known_flags() -> Vec<String> {
+    let mut flags: Vec<String> = PLATFORM_MAP.values().flatten().cloned().collect();
+    flags.sort();
+    flags.dedup();
+    flags
+}
+
+// Find the most similar name for "did you mean" suggestion, exp: `isWeChatMiniProgram` for `isWechatMiniProgram`.
+fn find_similar_name<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    let name = name.to_lowercase();
+    let max_distance = std::cmp::max(name.chars().count() / 3, 1);
+    candidates
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|(a, a_name), (b, b_name)| a.cmp(b).then(a_name.cmp(b_name)))
+        .map(|(_, candidate)| candidate.to_string())
+}
 
-        return new_module_items;
+// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut prev = distances[0];
+        distances[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let cur = distances[j + 1];
+            distances[j + 1] = if a_char == *b_char {
+                prev
+            } else {
+                1 + prev.min(cur).min(distances[j])
+            };
+            prev = cur;
+        }
     }
+    distances[b.len()]
 }
 
 // Check import source whether @uni/env or universal-env.
@@ -159,22 +918,400 @@ fn check_source(source: &str) -> bool {
     source == "universal-env" || source == "@uni/env"
 }
 
-// Insert variable declarator into module items, exp: var isWeb = true.
-fn insert_decls_into_module_items(decls: Vec<VarDeclarator>, module_items: &mut Vec<ModuleItem>) {
-    if decls.len() > 0 {
-        module_items.insert(
-            0,
-            ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
-                span: DUMMY_SP,
-                kind: VarDeclKind::Var,
-                declare: false,
-                decls,
-                ctxt: SyntaxContext::empty()
-            })))),
-        )
+// Check whether expression is a require call of env source, such as require('universal-env').
+fn is_env_require(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            args,
+            ..
+        }) => {
+            matches!(&**callee, Expr::Ident(ident) if &*ident.sym == "require")
+                && args.len() == 1
+                && matches!(&*args[0].expr, Expr::Lit(Lit::Str(str)) if check_source(&str.value))
+        }
+        _ => false,
+    }
+}
+
+/// Check file platform before running [KeepPlatformPatcher].
+struct FilePlatformPatcher<C: Comments> {
+    patcher: KeepPlatformPatcher,
+    comments: Option<C>,
+    filename: Option<String>,
+    file_suffix: bool,
+    file_mismatch: FileMismatch,
+}
+
+impl<C: Comments> FilePlatformPatcher<C> {
+    fn disabled(comments: Option<C>) -> Self {
+        FilePlatformPatcher {
+            patcher: KeepPlatformPatcher::default(),
+            comments,
+            filename: None,
+            file_suffix: false,
+            file_mismatch: FileMismatch::default(),
+        }
+    }
+
+    // Get platforms declared by pragma on the program or its first item, such as
+    // /* @platform weex */, or by filename suffix.
+    fn get_file_platforms(&self, positions: &[BytePos]) -> Option<Vec<String>> {
+        for pos in positions {
+            let pragma = self.comments.get_leading(*pos).and_then(|comments| {
+                comments.iter().find_map(|comment| parse_platform_pragma(&comment.text).map(|platforms| (platforms, comment.span)))
+            });
+            let Some((platforms, span)) = pragma else {
+                continue;
+            };
+            // Unknown names are ignored, so that a typo does not empty the module.
+            let (platforms, unknown_platforms): (Vec<String>, Vec<String>) =
+                platforms.into_iter().partition(|platform| PLATFORM_MAP.contains_key(platform));
+            for platform in unknown_platforms {
+                self.patcher.report(
+                    span,
+                    &format!("keep-platform: unknown platform `{}` in `@platform` pragma is ignored", platform),
+                    find_similar_name(&platform, PLATFORM_MAP.keys()),
+                );
+            }
+            if !platforms.is_empty() {
+                return Some(platforms);
+            }
+        }
+        if self.file_suffix {
+            return self.filename.as_deref().and_then(get_platform_suffix).map(|platform| vec![platform]);
+        }
+        None
+    }
+
+    fn is_mismatched_file(&self, positions: &[BytePos]) -> bool {
+        self.patcher.enabled
+            && self
+                .get_file_platforms(positions)
+                .is_some_and(|file_platforms| !file_platforms.iter().any(|platform| self.patcher.platforms.contains(platform)))
+    }
+}
+
+impl<C: Comments> Fold for FilePlatformPatcher<C> {
+    fn fold_module(&mut self, module: Module) -> Module {
+        let mut positions = vec![module.span.lo];
+        positions.extend(module.body.first().map(|item| item.span().lo));
+        if self.is_mismatched_file(&positions) {
+            // Replace module with an empty module or stubs of its exports.
+            let body = if self.file_mismatch == FileMismatch::Stub {
+                create_export_stubs(&module.body)
+            } else {
+                vec![]
+            };
+            return Module { body, ..module };
+        }
+        module.fold_with(&mut self.patcher)
+    }
+
+    fn fold_script(&mut self, script: Script) -> Script {
+        let mut positions = vec![script.span.lo];
+        positions.extend(script.body.first().map(|stmt| stmt.span().lo));
+        if self.is_mismatched_file(&positions) {
+            return Script { body: vec![], ..script };
+        }
+        script.fold_with(&mut self.patcher)
+    }
+}
+
+// Parse platforms of pragma comment, such as `@platform weex, kraken`. Only a whole `@platform`
+// tag is a pragma, so `@platform-specific` in a JSDoc is not.
+fn parse_platform_pragma(text: &str) -> Option<Vec<String>> {
+    let mut tokens = text
+        .split(|c: char| c == ',' || c == '*' || c.is_whitespace())
+        .filter(|token| !token.is_empty());
+    tokens.find(|token| *token == "@platform")?;
+    let platforms: Vec<String> = tokens
+        .take_while(|token| !token.starts_with('@'))
+        .map(|platform| platform.to_string())
+        .collect();
+    if platforms.is_empty() {
+        None
+    } else {
+        Some(platforms)
+    }
+}
+
+// Get platform suffix of filename, such as `weex` of `Foo.weex.tsx`.
+fn get_platform_suffix(filename: &str) -> Option<String> {
+    let basename = filename.rsplit(['/', '\\']).next()?;
+    let mut segments = basename.rsplit('.');
+    // Skip extension
+    segments.next()?;
+    let suffix = segments.next()?;
+    // Filename like `weex.js` has no platform suffix
+    segments.next()?;
+    if PLATFORM_MAP.contains_key(suffix) {
+        Some(suffix.to_string())
+    } else {
+        None
+    }
+}
+
+// Create stubs for exports of module, such as:
+// const __platform_stub__ = void 0; export { __platform_stub__ as foo, __platform_stub__ as default };
+fn create_export_stubs(items: &[ModuleItem]) -> Vec<ModuleItem> {
+    let export_names = collect_export_names(items);
+    if export_names.is_empty() {
+        return vec![];
+    }
+
+    let stub = Ident::new(JsWord::from("__platform_stub__"), DUMMY_SP, SyntaxContext::empty());
+    vec![
+        ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![create_var_decl(
+                stub.clone(),
+                Option::Some(Box::new(Expr::Unary(UnaryExpr {
+                    span: DUMMY_SP,
+                    op: op!("void"),
+                    arg: Box::new(Expr::Lit(Lit::Num(Number {
+                        span: DUMMY_SP,
+                        value: 0.0,
+                        raw: None,
+                    }))),
+                }))),
+                DUMMY_SP,
+            )],
+            ctxt: SyntaxContext::empty(),
+        })))),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+            span: DUMMY_SP,
+            specifiers: export_names
+                .into_iter()
+                .map(|name| {
+                    ExportSpecifier::Named(ExportNamedSpecifier {
+                        span: DUMMY_SP,
+                        orig: ModuleExportName::Ident(stub.clone()),
+                        exported: Some(create_module_export_name(name)),
+                        is_type_only: false,
+                    })
+                })
+                .collect(),
+            src: None,
+            type_only: false,
+            with: None,
+        })),
+    ]
+}
+
+// Collect names of explicit exports, `export *` is not included.
+fn collect_export_names(items: &[ModuleItem]) -> Vec<JsWord> {
+    let mut export_names: Vec<JsWord> = vec![];
+    for item in items.iter() {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => match &export_decl.decl {
+                Decl::Class(class_decl) => export_names.push(class_decl.ident.sym.clone()),
+                Decl::Fn(fn_decl) => export_names.push(fn_decl.ident.sym.clone()),
+                Decl::Var(var_decl) => {
+                    let ids: Vec<Ident> = find_pat_ids(&var_decl.decls);
+                    export_names.extend(ids.into_iter().map(|id| id.sym));
+                }
+                Decl::TsEnum(ts_enum) => export_names.push(ts_enum.id.sym.clone()),
+                _ => {}
+            },
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named)) if !export_named.type_only => {
+                for specifier in export_named.specifiers.iter() {
+                    match specifier {
+                        ExportSpecifier::Named(ExportNamedSpecifier {
+                            orig,
+                            exported,
+                            is_type_only: false,
+                            ..
+                        }) => export_names.push(get_module_export_name(exported.as_ref().unwrap_or(orig))),
+                        ExportSpecifier::Namespace(ExportNamespaceSpecifier { name, .. }) => {
+                            export_names.push(get_module_export_name(name))
+                        }
+                        ExportSpecifier::Default(ExportDefaultSpecifier { exported }) => {
+                            export_names.push(exported.sym.clone())
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                decl: DefaultDecl::Class(_) | DefaultDecl::Fn(_),
+                ..
+            }))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(_)) => {
+                export_names.push(JsWord::from("default"))
+            }
+            _ => {}
+        }
+    }
+    export_names
+}
+
+fn get_module_export_name(name: &ModuleExportName) -> JsWord {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.clone(),
+        ModuleExportName::Str(str) => str.value.clone(),
+    }
+}
+
+// Create export name, use string literal when the name is not a valid identifier.
+fn create_module_export_name(name: JsWord) -> ModuleExportName {
+    if is_valid_binding(&name) || &*name == "default" {
+        ModuleExportName::Ident(Ident::new(name, DUMMY_SP, SyntaxContext::empty()))
+    } else {
+        ModuleExportName::Str(Str {
+            span: DUMMY_SP,
+            value: name,
+            raw: None,
+        })
+    }
+}
+
+/// Collect all identifiers referenced by visited nodes.
+#[derive(Default)]
+struct IdentCollector {
+    ids: FxHashSet<Id>,
+}
+
+impl Visit for IdentCollector {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.ids.insert(ident.to_id());
+    }
+}
+
+// Get string value of JSX attribute with the given name, such as platform="weex".
+fn get_jsx_attr_value(attr: &JSXAttrOrSpread, name: &str) -> Option<String> {
+    match attr {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            name: JSXAttrName::Ident(ident),
+            value: Some(JSXAttrValue::Lit(Lit::Str(str))),
+            ..
+        }) if &*ident.sym == name => Some(str.value.to_string()),
+        _ => None,
+    }
+}
+
+// Get dot path of identifier or member expression, such as `process.env.PLATFORM`.
+fn get_expr_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(MemberExpr { obj, prop, .. }) => {
+            let prop = match prop {
+                MemberProp::Ident(ident) => ident.sym.to_string(),
+                MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                    Expr::Lit(Lit::Str(str)) => str.value.to_string(),
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            Some(format!("{}.{}", get_expr_path(obj)?, prop))
+        }
+        Expr::Paren(ParenExpr { expr, .. }) => get_expr_path(expr),
+        _ => None,
+    }
+}
+
+// Get root identifier of identifier or member expression, such as `process` of `process.env.PLATFORM`.
+fn get_expr_root(expr: &Expr) -> Option<&Ident> {
+    match expr {
+        Expr::Ident(ident) => Some(ident),
+        Expr::Member(MemberExpr { obj, .. }) => get_expr_root(obj),
+        Expr::Paren(ParenExpr { expr, .. }) => get_expr_root(expr),
+        _ => None,
     }
 }
 
+// Create literal expression by json value, such as: "web"
+fn create_value_expr(value: &Value, span: Span) -> Expr {
+    match value {
+        Value::Null => Expr::Lit(Lit::Null(Null { span })),
+        Value::Bool(value) => create_bool_expr(*value, span),
+        Value::Number(value) => Expr::Lit(Lit::Num(Number {
+            span,
+            value: value.as_f64().unwrap_or_default(),
+            raw: None,
+        })),
+        Value::String(value) => Expr::Lit(Lit::Str(Str {
+            span,
+            value: JsWord::from(value.as_str()),
+            raw: None,
+        })),
+        Value::Array(values) => Expr::Array(ArrayLit {
+            span,
+            elems: values
+                .iter()
+                .map(|value| {
+                    Some(ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(create_value_expr(value, span)),
+                    })
+                })
+                .collect(),
+        }),
+        Value::Object(values) => Expr::Object(ObjectLit {
+            span,
+            props: values
+                .iter()
+                .map(|(key, value)| {
+                    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Str(Str {
+                            span,
+                            value: JsWord::from(key.as_str()),
+                            raw: None,
+                        }),
+                        value: Box::new(create_value_expr(value, span)),
+                    })))
+                })
+                .collect(),
+        }),
+    }
+}
+
+// Create exported const declaration, exp: export const isWeb = true.
+fn create_export_const_decl(decls: Vec<VarDeclarator>, span: Span) -> ModuleItem {
+    let Stmt::Decl(decl) = create_const_decl(decls, span) else {
+        unreachable!();
+    };
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { span, decl }))
+}
+
+// Create method call expression, exp: Promise.resolve(arg).
+fn create_method_call(obj: Expr, method: &str, arg: Expr, span: Span) -> Expr {
+    Expr::Call(CallExpr {
+        span,
+        callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            span,
+            obj: Box::new(obj),
+            prop: MemberProp::Ident(create_jsword_ident(method)),
+        }))),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(arg),
+        }],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
+
+// Check whether the name can be used as a binding, exp: isWeb.
+fn is_valid_binding(name: &str) -> bool {
+    Ident::verify_symbol(name).is_ok()
+}
+
+// Create const declaration, exp: const isWeb = true.
+fn create_const_decl(decls: Vec<VarDeclarator>, span: Span) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span,
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls,
+        ctxt: SyntaxContext::empty(),
+    })))
+}
+
 // Create Ident by jsword.
 fn create_jsword_ident(value: &str) -> IdentName {
     IdentName {
@@ -184,25 +1321,25 @@ fn create_jsword_ident(value: &str) -> IdentName {
 }
 
 // Create variable declaration
-fn create_var_decl(id: Ident, init: Option<Box<Expr>>) -> VarDeclarator {
+fn create_var_decl(id: Ident, init: Option<Box<Expr>>, span: Span) -> VarDeclarator {
     let decl_name = Pat::Ident(BindingIdent {
-        id: id,
+        id,
         type_ann: Default::default(),
     });
 
     VarDeclarator {
         name: decl_name,
         init,
-        span: DUMMY_SP,
+        span,
         definite: false,
     }
 }
 
 // Create bool expr, such as: true
-fn create_bool_expr(value: bool) -> Expr {
+fn create_bool_expr(value: bool, span: Span) -> Expr {
     Expr::Lit(Lit::Bool(Bool {
-        value: value,
-        span: Default::default(),
+        value,
+        span,
     }))
 }
 
@@ -214,6 +1351,10 @@ pub fn process_transform(program: Program, _metadata: TransformPluginProgramMeta
             .expect("failed to get plugin config for keep-platform"),
     )
     .expect("invalid config for keep-platform");
+    let context = KeepPlatformContext {
+        platform: _metadata.get_experimental_context(PLATFORM_CONTEXT_KEY),
+        filename: _metadata.get_context(&TransformPluginMetadataContextKind::Filename),
+    };
 
-    program.fold_with(&mut keep_platform(platform))
+    program.fold_with(&mut keep_platform_with_context(platform, context, _metadata.comments))
 }
@@ -1,9 +1,30 @@
-use std::path::PathBuf;
+use std::{
+  collections::HashMap,
+  fs,
+  path::PathBuf,
+  sync::{Arc, Mutex},
+};
 use swc_core::{
-  ecma::transforms::testing::{test_fixture, FixtureTestConfig},
+  common::{
+    chain,
+    errors::{DiagnosticBuilder, Emitter as DiagnosticEmitter, Handler, HANDLER},
+    Mark,
+  },
+  ecma::ast::Program,
+  ecma::codegen::{text_writer::JsWriter, Emitter},
+  ecma::parser::{EsSyntax, Syntax},
+  ecma::transforms::{
+    base::resolver,
+    testing::{test_fixture, FixtureTestConfig, Tester},
+  },
+  ecma::visit::FoldWith,
 };
+use serde_json::json;
 use testing::fixture;
-use swc_plugin_keep_platform::{keep_platform, KeepPlatformConfig};
+use swc_plugin_keep_platform::{
+  keep_platform, keep_platform_with_context, FileMismatch, KeepPlatformConfig, KeepPlatformContext,
+  KeepPlatformOptions, UnknownFlags,
+};
 
 #[fixture("tests/fixture/empty/input.js")]
 fn fixture_empty(input: PathBuf) {
@@ -97,6 +118,24 @@ fn fixture_web(input: PathBuf) {
 }
 
 
+#[fixture("tests/fixture/import-after-use/input.js")]
+fn fixture_import_after_use(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatform(String::from("web")))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
 #[fixture("tests/fixture/namedexport/input.js")]
 fn fixture_named_export(input: PathBuf) {
   let parent = input.parent().unwrap();
@@ -132,3 +171,473 @@ fn fixture_default_export(input: PathBuf) {
     },
   );
 }
+
+#[fixture("tests/fixture/multi-platform/input.js")]
+fn fixture_multi_platform(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatforms(vec![String::from("web"), String::from("kraken")]))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/unknown-flags/**/input.js")]
+fn fixture_unknown_flags(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::Options(KeepPlatformOptions {
+        platforms: vec![String::from("web"), String::from("kraken")],
+        unknown_flags: UnknownFlags::Dynamic,
+        ..Default::default()
+      }))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/bool/false/input.js")]
+fn fixture_bool_false(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform_with_context(
+        KeepPlatformConfig::Bool(false),
+        KeepPlatformContext {
+          platform: Some(String::from("weex")),
+          ..Default::default()
+        },
+        Some(_t.comments.clone()),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/bool/true/input.js")]
+fn fixture_bool_true(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform_with_context(
+        KeepPlatformConfig::Bool(true),
+        KeepPlatformContext {
+          platform: Some(String::from("weex")),
+          ..Default::default()
+        },
+        Some(_t.comments.clone()),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/bool/true-without-context/input.js")]
+fn fixture_bool_true_without_context(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::Bool(true))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/require/**/input.js")]
+fn fixture_require(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatform(String::from("web")))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/define*/input.js")]
+fn fixture_define(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      let unresolved_mark = Mark::new();
+      chain!(resolver(unresolved_mark, Mark::new(), false), keep_platform(KeepPlatformConfig::Options(KeepPlatformOptions {
+        platforms: vec![String::from("web")],
+        define: HashMap::from([
+          (String::from("process.env.PLATFORM"), json!("web")),
+          (String::from("typeof window"), json!("object")),
+          (String::from("__IS_SERVER__"), json!(false)),
+          (String::from("process.env.VERSION"), json!(1)),
+        ]),
+        ..Default::default()
+      })))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/jsx/input.jsx")]
+fn fixture_jsx(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.jsx");
+
+  test_fixture(
+    Syntax::Es(EsSyntax {
+      jsx: true,
+      ..Default::default()
+    }),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::Options(KeepPlatformOptions {
+        platforms: vec![String::from("web")],
+        jsx_platform_attribute: Some(String::from("platform")),
+        ..Default::default()
+      }))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/file-platform/pragma/**/input.js")]
+fn fixture_file_pragma(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform_with_context(
+        KeepPlatformConfig::Options(KeepPlatformOptions {
+          platforms: vec![String::from("web")],
+          ..Default::default()
+        }),
+        KeepPlatformContext {
+          filename: None,
+          ..Default::default()
+        },
+        Some(_t.comments.clone()),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/file-platform/stub/input.js")]
+fn fixture_file_pragma_stub(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform_with_context(
+        KeepPlatformConfig::Options(KeepPlatformOptions {
+          platforms: vec![String::from("web")],
+          file_mismatch: FileMismatch::Stub,
+          ..Default::default()
+        }),
+        KeepPlatformContext {
+          filename: None,
+          ..Default::default()
+        },
+        Some(_t.comments.clone()),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/file-platform/suffix/input.js")]
+fn fixture_file_suffix(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform_with_context(
+        KeepPlatformConfig::Options(KeepPlatformOptions {
+          platforms: vec![String::from("web")],
+          file_suffix: true,
+          ..Default::default()
+        }),
+        KeepPlatformContext {
+          filename: Some(String::from("src/components/Foo.weex.js")),
+          ..Default::default()
+        },
+        Some(_t.comments.clone()),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/sourcemap/input.js")]
+fn fixture_sourcemap(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatform(String::from("web")))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+
+  // Generated declarations should be mapped to the import line.
+  Tester::run(|tester| {
+    let src = fs::read_to_string(&input).unwrap();
+    let program = tester.apply_transform(
+      keep_platform(KeepPlatformConfig::KeepPlatform(String::from("web"))),
+      "input.js",
+      Default::default(),
+      Some(true),
+      &src,
+    )?;
+    let mut buf = vec![];
+    let mut src_map = vec![];
+    {
+      let mut emitter = Emitter {
+        cfg: Default::default(),
+        cm: tester.cm.clone(),
+        comments: None,
+        wr: JsWriter::new(tester.cm.clone(), "\n", &mut buf, Some(&mut src_map)),
+      };
+      emitter.emit_program(&program).unwrap();
+    }
+    let code = String::from_utf8(buf).unwrap();
+    let source_map = tester.cm.build_source_map(&src_map);
+    let import_line = src.lines().position(|line| line.contains("universal-env")).unwrap();
+    let decl_line = code.lines().position(|line| line.starts_with("const isWeb")).unwrap();
+    let token = source_map.lookup_token(decl_line as u32, 0).unwrap();
+    assert_eq!(token.get_src_line(), import_line as u32);
+    Ok(())
+  });
+}
+
+#[fixture("tests/fixture/reexport/**/input.js")]
+fn fixture_reexport(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatform(String::from("web")))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/diagnostics/warning/input.js")]
+fn fixture_diagnostics_warning(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::KeepPlatforms(vec![String::from("web"), String::from("wechat-miniprogam")]))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+
+  assert_eq!(
+    collect_diagnostics(
+      KeepPlatformConfig::KeepPlatforms(vec![String::from("web"), String::from("wechat-miniprogam")]),
+      &fs::read_to_string(&input).unwrap(),
+    ),
+    vec![
+      "warning: keep-platform: unknown platform `wechat-miniprogam`, all platform flags will be `false` (did you mean `wechat-miniprogram`?)",
+      "warning: keep-platform: unknown platform flag `isWechatMiniProgram` is replaced with `false` (did you mean `isWeChatMiniProgram`?)",
+      "warning: keep-platform: unknown platform flag `isAndroid` is replaced with `false`",
+    ]
+  );
+}
+
+struct DiagnosticCollector(Arc<Mutex<Vec<String>>>);
+
+impl DiagnosticEmitter for DiagnosticCollector {
+  fn emit(&mut self, db: &DiagnosticBuilder<'_>) {
+    let mut message = format!("{}: {}", db.level, db.message());
+    for child in db.children.iter() {
+      message.push_str(&format!(" ({})", child.message().trim_start_matches("help: ")));
+    }
+    self.0.lock().unwrap().push(message);
+  }
+}
+
+#[fixture("tests/fixture/diagnostics/error/input.js")]
+fn fixture_diagnostics_error(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_platform(KeepPlatformConfig::Options(KeepPlatformOptions {
+        platforms: vec![String::from("Web")],
+        strict: true,
+        ..Default::default()
+      }))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      allow_error: true,
+      ..Default::default()
+    },
+  );
+}
+
+// Warnings are not emitted by the handler of fixture tests, so collect them manually.
+fn collect_diagnostics(config: KeepPlatformConfig, src: &str) -> Vec<String> {
+  let diagnostics: Arc<Mutex<Vec<String>>> = Default::default();
+  Tester::run(|tester| {
+    let handler = Handler::with_emitter(true, false, Box::new(DiagnosticCollector(diagnostics.clone())));
+    let comments = tester.comments.clone();
+    HANDLER.set(&handler, || {
+      tester.apply_transform(
+        keep_platform_with_context(config, Default::default(), Some(comments)),
+        "input.js",
+        Default::default(),
+        Some(true),
+        src,
+      )
+    })?;
+    Ok(())
+  });
+  let diagnostics = diagnostics.lock().unwrap().clone();
+  diagnostics
+}
+
+#[test]
+fn file_pragma_unknown_platform() {
+  assert_eq!(
+    collect_diagnostics(
+      KeepPlatformConfig::KeepPlatform(String::from("web")),
+      &fs::read_to_string("tests/fixture/file-platform/pragma/unknown/input.js").unwrap(),
+    ),
+    vec!["warning: keep-platform: unknown platform `weexx` in `@platform` pragma is ignored (did you mean `weex`?)"]
+  );
+}
+
+#[test]
+fn namespace_unknown_flags() {
+  for import in ["import * as env from 'universal-env';", "const env = require('universal-env');"] {
+    let src = format!("{}\nconsole.log(env.isWeb, env.isWechatMiniProgram, env['isAndroid']);", import);
+    assert_eq!(
+      collect_diagnostics(KeepPlatformConfig::KeepPlatform(String::from("web")), &src),
+      vec![
+        "warning: keep-platform: unknown platform flag `isWechatMiniProgram` of env namespace object is `undefined` (did you mean `isWeChatMiniProgram`?)",
+        "warning: keep-platform: unknown platform flag `isAndroid` of env namespace object is `undefined`",
+      ]
+    );
+  }
+}
+
+// The transform also runs when a `Module` or `Script` is folded instead of a `Program`.
+#[test]
+fn fold_module_and_script() {
+  Tester::run(|tester| {
+    let config = || KeepPlatformConfig::KeepPlatform(String::from("web"));
+    let module = tester
+      .parse_module("input.js", "import { isWeb } from 'universal-env';\nconsole.log(isWeb);")?
+      .fold_with(&mut keep_platform(config()));
+    let module_code = tester.print(&Program::Module(module), &tester.comments.clone());
+    assert_eq!(module_code.trim(), "const isWeb = true;\nconsole.log(isWeb);");
+
+    let comments = tester.comments.clone();
+    let mismatched = tester
+      .parse_module("input.js", "/* @platform weex */\nexport const value = 1;")?
+      .fold_with(&mut keep_platform_with_context(config(), Default::default(), Some(comments)));
+    assert!(mismatched.body.is_empty());
+
+    let script = tester
+      .with_parser("input.js", Default::default(), "const { isWeb } = require('universal-env');", |parser| {
+        parser.parse_script()
+      })?
+      .fold_with(&mut keep_platform(config()));
+    let script_code = tester.print(&Program::Script(script), &tester.comments.clone());
+    assert_eq!(script_code.trim(), "const isWeb = true;");
+    Ok(())
+  });
+}
@@ -0,0 +1,6 @@
+const isWeb = false, isWeex = true;
+if (isWeb) {
+    console.log('This is web');
+} else if (isWeex) {
+    console.log('This is weex');
+}
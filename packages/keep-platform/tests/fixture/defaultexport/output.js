@@ -1,2 +1,5 @@
-var isWeb = true, isWeex = false;
-export default { isWeb, isWeex };
\ No newline at end of file
+const isWeb = true, isWeex = false;
+export default {
+    isWeb,
+    isWeex
+};
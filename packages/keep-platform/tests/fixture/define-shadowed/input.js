@@ -0,0 +1,5 @@
+function f(__IS_SERVER__, process) {
+  return [__IS_SERVER__, process.env.PLATFORM];
+}
+
+console.log(__IS_SERVER__, process.env.PLATFORM, f);
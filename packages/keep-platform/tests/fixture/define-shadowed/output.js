@@ -0,0 +1,7 @@
+function f(__IS_SERVER__, process) {
+    return [
+        __IS_SERVER__,
+        process.env.PLATFORM
+    ];
+}
+console.log(false, "web", f);
@@ -0,0 +1,2 @@
+const env = { __IS_SERVER__ };
+console.log(env);
@@ -0,0 +1,4 @@
+const env = {
+    __IS_SERVER__: false
+};
+console.log(env);
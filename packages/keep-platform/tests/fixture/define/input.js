@@ -0,0 +1,12 @@
+import { isWeb } from 'universal-env';
+
+if (isWeb && process.env.PLATFORM === 'web') {
+  console.log('This is web', process.env['VERSION']);
+}
+
+if (typeof window !== 'undefined' && !__IS_SERVER__) {
+  console.log('This is browser');
+}
+
+process.env.PLATFORM = 'weex';
+console.log(process.env.NODE_ENV, typeof document);
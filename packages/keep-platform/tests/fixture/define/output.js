@@ -0,0 +1,9 @@
+const isWeb = true;
+if (isWeb && "web" === 'web') {
+    console.log('This is web', 1);
+}
+if ("object" !== 'undefined' && !false) {
+    console.log('This is browser');
+}
+process.env.PLATFORM = 'weex';
+console.log(process.env.NODE_ENV, typeof document);
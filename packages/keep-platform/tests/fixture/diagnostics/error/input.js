@@ -0,0 +1,9 @@
+import { isWeb, isWechatMiniProgram, isAndroid } from 'universal-env';
+
+if (isWeb) {
+  console.log('This is web');
+} else if (isWechatMiniProgram) {
+  console.log('This is wechat miniprogram');
+} else if (isAndroid) {
+  console.log('This is android');
+}
@@ -0,0 +1,8 @@
+const isWeb = false, isWechatMiniProgram = false, isAndroid = false;
+if (isWeb) {
+    console.log('This is web');
+} else if (isWechatMiniProgram) {
+    console.log('This is wechat miniprogram');
+} else if (isAndroid) {
+    console.log('This is android');
+}
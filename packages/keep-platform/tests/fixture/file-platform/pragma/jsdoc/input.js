@@ -0,0 +1,2 @@
+/** Uses @platform-specific styles */
+export const styles = { color: 'red' };
@@ -0,0 +1,3 @@
+/** Uses @platform-specific styles */ export const styles = {
+    color: 'red'
+};
@@ -0,0 +1,6 @@
+/* @platform weex, web */
+import { isWeb } from 'universal-env';
+
+export default function Swiper() {
+  return isWeb;
+}
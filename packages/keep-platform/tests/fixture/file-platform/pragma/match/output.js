@@ -0,0 +1,4 @@
+/* @platform weex, web */ const isWeb = true;
+export default function Swiper() {
+    return isWeb;
+}
@@ -0,0 +1,7 @@
+/* @platform weex */
+import { isWeex } from 'universal-env';
+import Slider from 'weex-slider';
+
+export default function Swiper() {
+  return isWeex && Slider;
+}
@@ -0,0 +1 @@
+/* @platform weex */ 
@@ -0,0 +1,2 @@
+/* @platform web, weexx */
+export const styles = { color: 'red' };
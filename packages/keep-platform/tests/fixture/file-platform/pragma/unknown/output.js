@@ -0,0 +1,3 @@
+/* @platform web, weexx */ export const styles = {
+    color: 'red'
+};
@@ -0,0 +1,10 @@
+/**
+ * @platform weex
+ */
+import { isWeex } from 'universal-env';
+
+export const slider = isWeex, { a, b: [c] } = {};
+export function Swiper() {}
+export { slider as "weex-slider" };
+export * from 'weex-slider';
+export default Swiper;
@@ -0,0 +1,4 @@
+/**
+ * @platform weex
+ */ const __platform_stub__ = void 0;
+export { __platform_stub__ as slider, __platform_stub__ as a, __platform_stub__ as c, __platform_stub__ as Swiper, __platform_stub__ as "weex-slider", __platform_stub__ as default };
@@ -0,0 +1,3 @@
+import Slider from 'weex-slider';
+
+export default Slider;
@@ -0,0 +1,3 @@
+console.log(isWeb);
+
+import { isWeb } from 'universal-env';
@@ -0,0 +1,2 @@
+const isWeb = true;
+console.log(isWeb);
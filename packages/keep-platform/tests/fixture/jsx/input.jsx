@@ -0,0 +1,22 @@
+import { isWeb } from 'universal-env';
+import View from 'rax-view';
+import Text from 'rax-text';
+import WeexSlider, { SliderItem } from 'weex-slider';
+import Image from 'rax-image';
+
+export function Home() {
+  return (
+    <View>
+      <Text platform="web,kraken">{isWeb ? 'web' : 'other'}</Text>
+      <WeexSlider platform="weex">
+        <SliderItem />
+        <Image />
+      </WeexSlider>
+      <Image platform="weex" />
+    </View>
+  );
+}
+
+export function Weex() {
+  return <WeexSlider platform="weex" />;
+}
@@ -0,0 +1,13 @@
+import View from 'rax-view';
+import Text from 'rax-text';
+const isWeb = true;
+export function Home() {
+    return <View>
+      <Text>{isWeb ? 'web' : 'other'}</Text>
+      
+      
+    </View>;
+}
+export function Weex() {
+    return null;
+}
@@ -1,4 +1,4 @@
-var isWeb = true, isWeex = false, isKraken = true;
+const isWeb = true, isWeex = false, isKraken = true;
 if (isWeb) {
     console.log('This is web');
 } else if (isWeex) {
@@ -0,0 +1,7 @@
+import { isWeb, isWeex, isKraken } from 'universal-env';
+
+if (isWeb && isKraken) {
+  console.log('This is web and kraken');
+} else if (isWeex) {
+  console.log('This is weex');
+}
@@ -0,0 +1,6 @@
+const isWeb = true, isWeex = false, isKraken = true;
+if (isWeb && isKraken) {
+    console.log('This is web and kraken');
+} else if (isWeex) {
+    console.log('This is weex');
+}
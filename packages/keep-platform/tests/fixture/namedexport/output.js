@@ -1,2 +1,2 @@
-var isWeb = true, isWeex = false;
-export { isWeb, isWeex };
\ No newline at end of file
+const isWeb = true, isWeex = false;
+export { isWeb, isWeex };
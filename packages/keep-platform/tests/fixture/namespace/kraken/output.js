@@ -1,8 +1,7 @@
-var env = {
+const env = {
     isKraken: true,
     isWeb: true
 };
-
 if (env.isKraken) {
     console.log('This is kraken');
 } else if (env.isWeex) {
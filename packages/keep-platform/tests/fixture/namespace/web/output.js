@@ -1,7 +1,6 @@
-var env = {
+const env = {
     isWeb: true
 };
-
 if (env.isWeb) {
     console.log('This is web');
 } else if (env.isWeex) {
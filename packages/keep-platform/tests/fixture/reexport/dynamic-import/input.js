@@ -0,0 +1,5 @@
+async function main() {
+  const { isWeb } = await import('universal-env');
+  const foo = await import('foo');
+  return isWeb && foo;
+}
@@ -0,0 +1,7 @@
+async function main() {
+    const { isWeb } = await Promise.resolve({
+        isWeb: true
+    });
+    const foo = await import('foo');
+    return isWeb && foo;
+}
@@ -0,0 +1,2 @@
+export * from '@uni/env';
+export * from 'foo';
@@ -0,0 +1,2 @@
+export const isBaiduSmartProgram = false, isByteDanceMicroApp = false, isKraken = false, isKuaiShouMiniProgram = false, isMiniApp = false, isNode = false, isWeChatMiniProgram = false, isWeChatMiniprogram = false, isWeb = true, isWeex = false;
+export * from 'foo';
@@ -0,0 +1,4 @@
+export { isWeb, isWeex as weex, default as universalEnv, isNode as "is-node" } from 'universal-env';
+export * as env from '@uni/env';
+export { isWeb as default } from '@uni/env';
+export { foo } from 'foo';
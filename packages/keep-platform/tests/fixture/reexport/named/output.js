@@ -0,0 +1,7 @@
+export { default as universalEnv, isNode as "is-node" } from 'universal-env';
+export const isWeb = true, weex = false;
+export const env = {
+    isWeb: true
+};
+export default true;
+export { foo } from 'foo';
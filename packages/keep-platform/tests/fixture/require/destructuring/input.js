@@ -0,0 +1,8 @@
+const { isWeb, isWeex: weex } = require('universal-env');
+const path = require('path');
+
+if (isWeb) {
+  console.log('This is web', path.sep);
+} else if (weex) {
+  console.log('This is weex');
+}
@@ -0,0 +1,7 @@
+const isWeb = true, weex = false;
+const path = require('path');
+if (isWeb) {
+    console.log('This is web', path.sep);
+} else if (weex) {
+    console.log('This is weex');
+}
@@ -0,0 +1,8 @@
+import { isWeex } from 'universal-env';
+const { isWeb } = require('universal-env');
+
+export default function () {
+  // Only top-level require is replaced
+  const { isNode } = require('universal-env');
+  return isWeb && !isWeex && !isNode;
+}
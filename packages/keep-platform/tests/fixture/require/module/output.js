@@ -0,0 +1,7 @@
+const isWeex = false;
+const isWeb = true;
+export default function() {
+    // Only top-level require is replaced
+    const { isNode } = require('universal-env');
+    return isWeb && !isWeex && !isNode;
+}
@@ -0,0 +1,7 @@
+const env = require('@uni/env'), foo = 1;
+
+if (env.isWeb) {
+  console.log('This is web', foo);
+} else if (env.isWeex) {
+  console.log('This is weex');
+}
@@ -0,0 +1,8 @@
+const env = {
+    isWeb: true
+}, foo = 1;
+if (env.isWeb) {
+    console.log('This is web', foo);
+} else if (env.isWeex) {
+    console.log('This is weex');
+}
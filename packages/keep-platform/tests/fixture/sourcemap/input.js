@@ -0,0 +1,9 @@
+import { createElement } from 'rax';
+import { isWeb, isWeex } from 'universal-env';
+
+export default function App() {
+  if (isWeex) {
+    throw new Error('weex is not supported');
+  }
+  return createElement('div', null, isWeb);
+}
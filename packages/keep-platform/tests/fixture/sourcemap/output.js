@@ -0,0 +1,8 @@
+import { createElement } from 'rax';
+const isWeb = true, isWeex = false;
+export default function App() {
+    if (isWeex) {
+        throw new Error('weex is not supported');
+    }
+    return createElement('div', null, isWeb);
+}
@@ -0,0 +1,11 @@
+import { isWeb, isWeex, isAndroid } from 'universal-env';
+
+if (isWeb) {
+  console.log('This is web');
+} else if (isWeex) {
+  console.log('This is weex');
+}
+
+if (isAndroid) {
+  console.log('This is android');
+}
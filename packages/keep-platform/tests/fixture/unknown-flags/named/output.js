@@ -0,0 +1,10 @@
+import { isAndroid } from 'universal-env';
+const isWeb = true, isWeex = false;
+if (isWeb) {
+    console.log('This is web');
+} else if (isWeex) {
+    console.log('This is weex');
+}
+if (isAndroid) {
+    console.log('This is android');
+}
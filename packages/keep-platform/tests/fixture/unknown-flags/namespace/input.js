@@ -0,0 +1,7 @@
+import * as env from '@uni/env';
+
+if (env.isKraken) {
+  console.log('This is kraken');
+} else if (env.isAndroid) {
+  console.log('This is android');
+}
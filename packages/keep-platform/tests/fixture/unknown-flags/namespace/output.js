@@ -0,0 +1,19 @@
+import * as _env from '@uni/env';
+const env = {
+    ..._env,
+    isBaiduSmartProgram: false,
+    isByteDanceMicroApp: false,
+    isKraken: true,
+    isKuaiShouMiniProgram: false,
+    isMiniApp: false,
+    isNode: false,
+    isWeChatMiniProgram: false,
+    isWeChatMiniprogram: false,
+    isWeb: true,
+    isWeex: false
+};
+if (env.isKraken) {
+    console.log('This is kraken');
+} else if (env.isAndroid) {
+    console.log('This is android');
+}
@@ -0,0 +1,5 @@
+export { isWeb, isAndroid } from 'universal-env';
+export * as env from '@uni/env';
+export * from '@uni/env';
+
+export const load = () => import('universal-env');
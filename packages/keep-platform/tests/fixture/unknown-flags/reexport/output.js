@@ -0,0 +1,31 @@
+export { isAndroid } from 'universal-env';
+export const isWeb = true;
+import * as _env from '@uni/env';
+export const env = {
+    ..._env,
+    isBaiduSmartProgram: false,
+    isByteDanceMicroApp: false,
+    isKraken: true,
+    isKuaiShouMiniProgram: false,
+    isMiniApp: false,
+    isNode: false,
+    isWeChatMiniProgram: false,
+    isWeChatMiniprogram: false,
+    isWeb: true,
+    isWeex: false
+};
+export * from '@uni/env';
+export const isBaiduSmartProgram = false, isByteDanceMicroApp = false, isKraken = true, isKuaiShouMiniProgram = false, isMiniApp = false, isNode = false, isWeChatMiniProgram = false, isWeChatMiniprogram = false, isWeex = false;
+export const load = ()=>import('universal-env').then((env)=>({
+            ...env,
+            isBaiduSmartProgram: false,
+            isByteDanceMicroApp: false,
+            isKraken: true,
+            isKuaiShouMiniProgram: false,
+            isMiniApp: false,
+            isNode: false,
+            isWeChatMiniProgram: false,
+            isWeChatMiniprogram: false,
+            isWeb: true,
+            isWeex: false
+        }));
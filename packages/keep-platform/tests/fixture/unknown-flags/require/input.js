@@ -0,0 +1,8 @@
+const { isWeb, isAndroid } = require('universal-env');
+const env = require('@uni/env');
+
+if (isWeb && env.isKraken) {
+  console.log('This is web and kraken');
+} else if (isAndroid || env.isAndroid) {
+  console.log('This is android');
+}
@@ -0,0 +1,19 @@
+const { isAndroid } = require('universal-env'), isWeb = true;
+const env = {
+    ...require('@uni/env'),
+    isBaiduSmartProgram: false,
+    isByteDanceMicroApp: false,
+    isKraken: true,
+    isKuaiShouMiniProgram: false,
+    isMiniApp: false,
+    isNode: false,
+    isWeChatMiniProgram: false,
+    isWeChatMiniprogram: false,
+    isWeb: true,
+    isWeex: false
+};
+if (isWeb && env.isKraken) {
+    console.log('This is web and kraken');
+} else if (isAndroid || env.isAndroid) {
+    console.log('This is android');
+}
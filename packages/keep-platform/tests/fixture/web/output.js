@@ -1,4 +1,4 @@
-var isWeb = true, isWeex = false;
+const isWeb = true, isWeex = false;
 if (isWeb) {
     console.log('This is web');
 } else if (isWeex) {
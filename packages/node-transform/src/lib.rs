@@ -1,77 +1,405 @@
+use fxhash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use swc_common::{
-    util::take::Take, SyntaxContext, DUMMY_SP
+    comments::{Comment, CommentKind, Comments, NoopComments},
+    plugin::metadata::TransformPluginMetadataContextKind,
+    util::take::Take, Mark, Span, SyntaxContext, DUMMY_SP
 };
 use swc_core::ecma::{
     ast::*,
     atoms::JsWord,
+    utils::find_pat_ids,
     visit::{Fold, FoldWith},
 
 };
 use swc_plugin_proxy::TransformPluginProgramMetadata;
 use swc_plugin_macro::plugin_transform;
 
-pub fn node_transform() -> impl Fold {
-    NodeTransform
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeTransformConfig {
+    /// Read imported bindings from the import object on every access instead of
+    /// copying them into local `const` declarations, so that later updates of
+    /// `let` exports are observed by the importer.
+    pub live_bindings: bool,
+    /// Start loading all static imports at once with `Promise.all` instead of
+    /// awaiting them one after another. Side effect imports like `import './polyfill'`
+    /// still wait for the imports before them and block the imports after them.
+    pub parallel_imports: bool,
+    /// Names of the runtime helpers used by the generated code.
+    pub helpers: RuntimeHelpers,
+    /// Replace `import.meta.url`, `import.meta.dirname` and `import.meta.filename`
+    /// with values of the filename in `NodeTransformContext`.
+    pub inline_import_meta: bool,
+    pub format: ModuleFormat,
+    /// Replace `import.meta.hot` with the hot context provided by the runner, and record
+    /// the bindings read from every import in [ModuleImport::used], so that the runner only
+    /// invalidates the importers of a changed export.
+    pub hmr: bool,
 }
 
-fn create_import_str(key: i32) -> String {
-    format!("__ice_import_{}__", key)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ModuleFormat {
+    /// `await __ice_import__("src")` and `__ice_exports__`, for the ice async module runner.
+    #[default]
+    #[serde(rename = "async")]
+    Async,
+    /// `require("src")` and `module.exports`, for scripts which run synchronously in a
+    /// CommonJS context. Runtime helpers are not used.
+    #[serde(rename = "commonjs")]
+    CommonJs,
 }
 
-fn create_var_decl(id: &str, init: Option<Box<Expr>>) -> VarDeclarator {
+/// Host provided information of the module being transformed.
+#[derive(Debug, Default, Clone)]
+pub struct NodeTransformContext {
+    /// Absolute filename of the module.
+    pub filename: Option<String>,
+}
+
+/// Prefix of the leading comment which carries the [ModuleInfo] of a transformed module as JSON,
+/// `/*__ice_module_info__ {"exports":["default"],"exportAll":[],"imports":[]}*/`.
+/// A `*/` in the JSON is written as `*\u002f`, which any JSON parser reads back as is.
+pub const MODULE_INFO_COMMENT: &str = "__ice_module_info__";
+
+/// Static imports and exports of a module, so that the runner can link modules
+/// and report missing exports before evaluating them.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleInfo {
+    /// Export names of the module, except the ones of `export * from 'src'`.
+    pub exports: Vec<String>,
+    /// Sources of `export * from 'src'`.
+    pub export_all: Vec<String>,
+    /// Sources of imports and re-exports, in source order.
+    pub imports: Vec<ModuleImport>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleImport {
+    pub source: String,
+    /// Imported names, `default` for default imports and `*` for namespace imports.
+    pub specifiers: Vec<String>,
+    /// Imported names which are read by the module in HMR mode, `*` when the module object
+    /// itself is read, like `console.log(ns)` or `export * from 'src'`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuntimeHelpers {
+    /// Loads a static import, `await __ice_import__("src")`.
+    pub import: String,
+    /// Replaces `import("src")`.
+    pub dynamic_import: String,
+    /// Exports object of the current module.
+    pub exports: String,
+    /// Re-exports every export of a module, `__ice_exports_all__(__ice_import_0__)`.
+    pub exports_all: String,
+    /// Replaces `import.meta`.
+    pub import_meta: String,
+    /// Name pattern of import variables, `{}` is replaced with the import index.
+    /// The index is appended to patterns without `{}`.
+    pub import_var: String,
+    /// Replaces `import.meta.hot` in HMR mode.
+    pub hot: String,
+}
+
+impl Default for RuntimeHelpers {
+    fn default() -> Self {
+        Self {
+            import: String::from("__ice_import__"),
+            dynamic_import: String::from("__ice_dynamic_import__"),
+            exports: String::from("__ice_exports__"),
+            exports_all: String::from("__ice_exports_all__"),
+            import_meta: String::from("__ice_import_meta__"),
+            import_var: String::from("__ice_import_{}__"),
+            hot: String::from("__ice_hot__"),
+        }
+    }
+}
+
+/// Runtime helpers and import variables share a private syntax context, so that
+/// hygiene renames user bindings which collide with them. The context is created when
+/// folding starts, because marks can only be created inside `GLOBALS.set`.
+struct Helpers {
+    names: RuntimeHelpers,
+    ctxt: SyntaxContext,
+    format: ModuleFormat,
+}
+
+impl Helpers {
+    fn ident(&self, name: &str) -> Ident {
+        Ident {
+            span: DUMMY_SP,
+            sym: JsWord::from(name),
+            optional: Default::default(),
+            ctxt: self.ctxt,
+        }
+    }
+
+    fn expr(&self, name: &str) -> Box<Expr> {
+        Box::new(Expr::Ident(self.ident(name)))
+    }
+
+    /// `__ice_import_0__.property`, with default interop of `require` for CommonJS.
+    fn import_member(&self, import_val: &str, property: &str) -> Box<Expr> {
+        if self.format == ModuleFormat::CommonJs && property == "default" {
+            return create_interop_default(self.expr(import_val));
+        }
+        create_member_expr(self.expr(import_val), property)
+    }
+
+    /// `__ice_exports__`, or `module.exports` for CommonJS.
+    fn exports_expr(&self) -> Box<Expr> {
+        match self.format {
+            ModuleFormat::Async => self.expr(&self.names.exports),
+            ModuleFormat::CommonJs => create_member_expr(create_ident_expr("module"), "exports"),
+        }
+    }
+}
+
+/// Note: `live_bindings` requires running `resolver` **before** running this,
+/// otherwise locals shadowing an imported name can not be told apart.
+pub fn node_transform(config: NodeTransformConfig) -> impl Fold {
+    node_transform_with_context(config, Default::default(), None::<NoopComments>)
+}
+
+/// Same as [node_transform], with host provided context. When `comments` is given, the
+/// [ModuleInfo] of the module is added as a leading comment, see [MODULE_INFO_COMMENT].
+pub fn node_transform_with_context<C: Comments>(
+    config: NodeTransformConfig,
+    context: NodeTransformContext,
+    comments: Option<C>,
+) -> impl Fold {
+    NodeTransform {
+        helpers: Helpers {
+            names: config.helpers.clone(),
+            ctxt: SyntaxContext::empty(),
+            format: config.format,
+        },
+        config,
+        context,
+        imported_bindings: Default::default(),
+        import_vals: Default::default(),
+        import_id: 0,
+        pending_imports: Default::default(),
+        ordered_imports: Default::default(),
+        module_info: Default::default(),
+        hmr_bindings: Default::default(),
+        comments,
+    }
+}
+
+// Same as `url.pathToFileURL(filename).href` of node for absolute filenames.
+fn create_file_url(filename: &str) -> String {
+    let path = filename.replace('\\', "/");
+    let mut url = String::from(if path.starts_with('/') { "file://" } else { "file:///" });
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/!$&'()*+,;=:@".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+// Values of `import.meta` properties in CommonJS, `url` is `require("url").pathToFileURL(__filename).href`.
+fn create_cjs_import_meta_value(property: &str) -> Option<Box<Expr>> {
+    match property {
+        "url" => Some(create_member_expr(
+            create_call(
+                create_member_expr(
+                    create_call(create_ident_expr("require"), vec![
+                        ExprOrSpread { spread: Take::dummy(), expr: create_str_expr("url") },
+                    ]),
+                    "pathToFileURL",
+                ),
+                vec![ExprOrSpread { spread: Take::dummy(), expr: create_ident_expr("__filename") }],
+            ),
+            "href",
+        )),
+        "filename" => Some(create_ident_expr("__filename")),
+        "dirname" => Some(create_ident_expr("__dirname")),
+        _ => None,
+    }
+}
+
+// ({ url: require("url").pathToFileURL(__filename).href, filename: __filename, dirname: __dirname })
+fn create_cjs_import_meta(span: Span) -> Expr {
+    Expr::Object(ObjectLit {
+        span,
+        props: ["url", "filename", "dirname"].into_iter().filter_map(|key| {
+            let value = create_cjs_import_meta_value(key)?;
+            Some(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from(key) }),
+                value,
+            }))))
+        }).collect(),
+    })
+}
+
+fn create_import_str(helpers: &Helpers, key: i32) -> String {
+    let import_var = &helpers.names.import_var;
+    if import_var.contains("{}") {
+        import_var.replace("{}", &key.to_string())
+    } else {
+        // Every import needs its own variable, so the index is appended to a pattern like `__imp__`.
+        format!("{}{}", import_var, key)
+    }
+}
+
+fn create_var_decl(id: Ident, init: Option<Box<Expr>>) -> VarDeclarator {
     let decl_name:Pat = Pat::Ident(BindingIdent {
-        id: Ident { span: DUMMY_SP, sym: JsWord::from(id), optional: Default::default(), ctxt: SyntaxContext::empty() },
+        id,
         type_ann: Default::default(),
     });
     VarDeclarator { span: DUMMY_SP, name: decl_name, init, definite: false }
 }
 
-fn create_member_decl(id: Ident, object_name: &str, property: &str) -> VarDeclarator {
+fn create_ident_expr(name: &str) -> Box<Expr> {
+    Box::new(Expr::Ident(Ident {
+        span: DUMMY_SP,
+        sym: JsWord::from(name),
+        optional: Default::default(),
+        ctxt: SyntaxContext::empty()
+    }))
+}
+
+fn is_valid_prop_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(Ident::is_valid_start) && chars.all(Ident::is_valid_continue)
+}
+
+// `obj.property`, or `obj["property"]` for names which are not identifiers.
+fn create_member_expr(obj: Box<Expr>, property: &str) -> Box<Expr> {
+    create_member_expr_with_span(obj, property, DUMMY_SP)
+}
+
+fn create_member_expr_with_span(obj: Box<Expr>, property: &str, span: Span) -> Box<Expr> {
+    let prop = if is_valid_prop_name(property) {
+        MemberProp::Ident(IdentName {
+            span: DUMMY_SP,
+            sym: JsWord::from(property)
+        })
+    } else {
+        MemberProp::Computed(ComputedPropName {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: JsWord::from(property),
+                raw: Default::default()
+            })))
+        })
+    };
+    Box::new(Expr::Member(
+        MemberExpr {
+            span,
+            obj,
+            prop,
+        }
+    ))
+}
+
+fn create_member_decl(id: Ident, helpers: &Helpers, import_val: &str, property: &str) -> VarDeclarator {
     VarDeclarator { span: DUMMY_SP, name: Pat::Ident(BindingIdent {
         id,
         type_ann: Default::default(),
-    }), init: Option::Some(Box::new(Expr::Member(
-        MemberExpr {
+    }), init: Option::Some(helpers.import_member(import_val, property)), definite: false }
+}
+
+/// `m && m.__esModule ? m.default : m`, the default export of a module loaded by `require`,
+/// which is `module.exports` itself for plain CommonJS modules like `path`.
+fn create_interop_default(module: Box<Expr>) -> Box<Expr> {
+    Box::new(Expr::Cond(CondExpr {
+        span: DUMMY_SP,
+        test: Box::new(Expr::Bin(BinExpr {
             span: DUMMY_SP,
-            obj: Box::new(Expr::Ident(Ident {
-                span: DUMMY_SP,
-                sym: JsWord::from(object_name),
-                optional: Default::default(),
-                ctxt: SyntaxContext::empty()
-            })),
-            prop: MemberProp::Ident(IdentName {
-                span: DUMMY_SP,
-                sym: JsWord::from(property)
-            })
-        }
-    ))), definite: false }
+            op: op!("&&"),
+            left: module.clone(),
+            right: create_member_expr(module.clone(), "__esModule"),
+        })),
+        cons: create_member_expr(module.clone(), "default"),
+        alt: module,
+    }))
+}
+
+/// `Object.defineProperty(module.exports, "__esModule", { value: true })`, so that CommonJS
+/// consumers compiled by Babel or TypeScript read `default` from the exports.
+fn create_es_module_flag(helpers: &Helpers) -> ModuleItem {
+    let value = PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from("value") }),
+        value: Box::new(Expr::Lit(Lit::Bool(Bool { span: DUMMY_SP, value: true }))),
+    })));
+    let expr = create_call(create_member_expr(create_ident_expr("Object"), "defineProperty"), vec![
+        ExprOrSpread { spread: Take::dummy(), expr: helpers.exports_expr() },
+        ExprOrSpread { spread: Take::dummy(), expr: create_str_expr("__esModule") },
+        ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Object(ObjectLit { span: DUMMY_SP, props: vec![value] })) },
+    ]);
+    ModuleItem::Stmt(Stmt::Expr(ExprStmt { span: DUMMY_SP, expr }))
+}
+
+/// A `__ice_import__` call loading a module.
+struct ImportCall {
+    import_val: String,
+    src: Str,
+    /// Span of the first import or export statement of the module.
+    span: Span,
+    /// Import attributes, passed to the runtime as the second argument.
+    with: Option<Box<ObjectLit>>,
 }
 
-fn create_import_decl(import_val: &str, import_source: &str) -> ModuleItem {
-    let call_args = vec![
+fn create_import_call(helpers: &Helpers, import: &ImportCall) -> Box<Expr> {
+    let mut call_args = vec![
         ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Lit(Lit::Str(Str {
-            span: DUMMY_SP, value: JsWord::from(import_source), raw: Default::default()
+            span: import.src.span, value: import.src.value.clone(), raw: Default::default()
         })))}
     ];
-    let decls: Vec<VarDeclarator> = vec![
-        create_var_decl(&import_val, Option::Some(Box::new(Expr::Await(
+    if helpers.format == ModuleFormat::CommonJs {
+        // require("src")
+        return create_call_with_span(create_ident_expr("require"), call_args, import.span);
+    }
+    if let Some(with) = &import.with {
+        call_args.push(ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Object(*with.clone())) });
+    }
+    create_call_with_span(helpers.expr(&helpers.names.import), call_args, import.span)
+}
+
+fn create_call(callee: Box<Expr>, args: Vec<ExprOrSpread>) -> Box<Expr> {
+    create_call_with_span(callee, args, DUMMY_SP)
+}
+
+fn create_call_with_span(callee: Box<Expr>, args: Vec<ExprOrSpread>, span: Span) -> Box<Expr> {
+    Box::new(Expr::Call(
+        CallExpr {
+            span,
+            callee: Callee::Expr(callee),
+            args,
+            type_args: Take::dummy(),
+            ctxt: SyntaxContext::empty()
+        }
+    ))
+}
+
+fn create_import_decl(helpers: &Helpers, import: &ImportCall) -> ModuleItem {
+    let init = match helpers.format {
+        ModuleFormat::Async => Box::new(Expr::Await(
             AwaitExpr {
-                span: DUMMY_SP,
-                arg: Box::new(Expr::Call(
-                    CallExpr {
-                        span: DUMMY_SP,
-                        callee: Callee::Expr(Box::new(Expr::Ident(Ident { ctxt: SyntaxContext::empty(), span: DUMMY_SP, sym: JsWord::from("__ice_import__"), optional: Default::default() }))),
-                        args: call_args,
-                        type_args: Take::dummy(),
-                        ctxt: SyntaxContext::empty()
-                    }
-                ))
+                span: import.span,
+                arg: create_import_call(helpers, import),
             }
-        ))))
+        )),
+        ModuleFormat::CommonJs => create_import_call(helpers, import),
+    };
+    let decls: Vec<VarDeclarator> = vec![
+        VarDeclarator { span: import.span, ..create_var_decl(helpers.ident(&import.import_val), Option::Some(init)) }
     ];
     
     ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
-        span: DUMMY_SP,
+        span: import.span,
         kind: VarDeclKind::Const,
         declare: false,
         decls,
@@ -79,84 +407,217 @@ fn create_import_decl(import_val: &str, import_source: &str) -> ModuleItem {
     }))))
 }
 
-fn create_define_export(name: &str, value: &str) -> ModuleItem {
-    ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+// const [__ice_import_0__, __ice_import_1__] = await Promise.all([__ice_import__("a"), __ice_import__("b")]);
+fn create_parallel_import_decl(helpers: &Helpers, imports: &[ImportCall]) -> ModuleItem {
+    let elems = imports.iter().map(|import| Some(Pat::Ident(BindingIdent {
+        id: helpers.ident(&import.import_val),
+        type_ann: Default::default(),
+    }))).collect();
+    let calls = imports.iter().map(|import| Some(ExprOrSpread {
+        spread: Take::dummy(),
+        expr: create_import_call(helpers, import),
+    })).collect();
+    ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
         span: DUMMY_SP,
-        expr: Box::new(Expr::Call(
-            CallExpr {
-                ctxt: SyntaxContext::empty(),
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Array(ArrayPat { span: DUMMY_SP, elems, optional: false, type_ann: Default::default() }),
+            init: Some(Box::new(Expr::Await(AwaitExpr {
                 span: DUMMY_SP,
-                callee: Callee::Expr(Box::new(Expr::Member(
-                    MemberExpr {
-                        span: DUMMY_SP,
-                        obj: Box::new(Expr::Ident(Ident { ctxt: SyntaxContext::empty(), span: DUMMY_SP, sym: JsWord::from("Object"), optional: Default::default() })),
-                        prop: MemberProp::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from("defineProperty")  })
-                    }
-                ))),
-                args: vec![
-                    ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Ident(Ident { span: DUMMY_SP, sym: JsWord::from("__ice_exports__"), optional: Default::default(), ctxt: SyntaxContext::empty() }))},
-                    ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Lit(Lit::Str(Str { span: DUMMY_SP, value: JsWord::from(name), raw: Default::default() })))},
-                    ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Object(ObjectLit {
+                arg: Box::new(Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    callee: Callee::Expr(create_member_expr(create_ident_expr("Promise"), "all")),
+                    args: vec![ExprOrSpread {
+                        spread: Take::dummy(),
+                        expr: Box::new(Expr::Array(ArrayLit { span: DUMMY_SP, elems: calls })),
+                    }],
+                    type_args: Take::dummy(),
+                    ctxt: SyntaxContext::empty(),
+                })),
+            }))),
+            definite: false,
+        }],
+        ctxt: SyntaxContext::empty(),
+    }))))
+}
+
+fn create_define_export(helpers: &Helpers, name: &str, value: Box<Expr>, span: Span) -> ModuleItem {
+    ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+        span,
+        expr: create_define_property(helpers.exports_expr(), create_str_expr(name), value),
+    }))
+}
+
+fn create_str_expr(value: &str) -> Box<Expr> {
+    Box::new(Expr::Lit(Lit::Str(Str { span: DUMMY_SP, value: JsWord::from(value), raw: Default::default() })))
+}
+
+// Object.defineProperty(exports, name, { enumerable: true, get() { return value; } })
+fn create_define_property(exports: Box<Expr>, name: Box<Expr>, value: Box<Expr>) -> Box<Expr> {
+    create_call(create_member_expr(create_ident_expr("Object"), "defineProperty"), vec![
+        ExprOrSpread { spread: Take::dummy(), expr: exports },
+        ExprOrSpread { spread: Take::dummy(), expr: name },
+        ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: vec![
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from("enumerable") }),
+                    value: Box::new(Expr::Lit(Lit::Bool(Bool { span: DUMMY_SP, value: true })))
+                }))),
+                PropOrSpread::Prop(Box::new(Prop::Getter(GetterProp {
+                    span: DUMMY_SP,
+                    key: PropName::Ident(IdentName { span: DUMMY_SP, sym: Default::default() }),
+                    body: Option::Some(BlockStmt {
+                        ctxt: SyntaxContext::empty(),
                         span: DUMMY_SP,
-                        props: vec![
-                            PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-                                key: PropName::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from("enumerable") }),
-                                value: Box::new(Expr::Lit(Lit::Bool(Bool { span: DUMMY_SP, value: true })))
-                            }))),
-                            PropOrSpread::Prop(Box::new(Prop::Getter(GetterProp {
+                        stmts: vec![
+                            Stmt::Return(ReturnStmt {
                                 span: DUMMY_SP,
-                                key: PropName::Ident(IdentName { span: DUMMY_SP, sym: Default::default() }),
-                                body: Option::Some(BlockStmt {
-                                    ctxt: SyntaxContext::empty(),
-                                    span: DUMMY_SP,
-                                    stmts: vec![
-                                        Stmt::Return(ReturnStmt {
-                                            span: DUMMY_SP,
-                                            arg: Option::Some(Box::new(Expr::Ident(Ident { ctxt: SyntaxContext::empty(), span: DUMMY_SP, sym: JsWord::from(value), optional: Default::default() })))
-                                        })
-                                    ]
-                                }),
-                                type_ann: Default::default()
-                            })))
+                                arg: Option::Some(value)
+                            })
                         ]
-                    }))},
-                ],
-                type_args: Take::dummy()
-            }
-        ))
-    }))
+                    }),
+                    type_ann: Default::default()
+                })))
+            ]
+        }))},
+    ])
+}
+
+/// `Object.defineProperty(_default, "name", { value: "default", configurable: true })`, the name
+/// an anonymous default export gets in ESM.
+fn create_default_name(binding: &Ident) -> ModuleItem {
+    let prop = |key: &str, value: Lit| PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Ident(IdentName { span: DUMMY_SP, sym: JsWord::from(key) }),
+        value: Box::new(Expr::Lit(value)),
+    })));
+    let expr = create_call(create_member_expr(create_ident_expr("Object"), "defineProperty"), vec![
+        ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Ident(binding.clone())) },
+        ExprOrSpread { spread: Take::dummy(), expr: create_str_expr("name") },
+        ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: vec![
+                prop("value", Lit::Str(Str { span: DUMMY_SP, value: JsWord::from("default"), raw: Default::default() })),
+                prop("configurable", Lit::Bool(Bool { span: DUMMY_SP, value: true })),
+            ],
+        }))},
+    ]);
+    ModuleItem::Stmt(Stmt::Expr(ExprStmt { span: DUMMY_SP, expr }))
 }
 
-fn create_call_expr(name: &str) -> ModuleItem {
+/// Anonymous function, arrow or class of `export default`, which gets its name from the export.
+fn get_anonymous_default(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Paren(ParenExpr { expr, .. }) => get_anonymous_default(expr),
+        Expr::Arrow(_) | Expr::Fn(FnExpr { ident: None, .. }) | Expr::Class(ClassExpr { ident: None, .. }) => Some(expr),
+        _ => None,
+    }
+}
+
+/// A static `name` member of a class replaces the name given by the default export.
+fn has_static_name(class: &Class) -> bool {
+    class.body.iter().any(|member| {
+        let key = match member {
+            ClassMember::ClassProp(ClassProp { is_static: true, key, .. }) => key,
+            ClassMember::Method(ClassMethod { is_static: true, key, .. }) => key,
+            _ => return false,
+        };
+        match key {
+            PropName::Ident(ident) => &*ident.sym == "name",
+            PropName::Str(str) => &*str.value == "name",
+            _ => false,
+        }
+    })
+}
+
+fn create_call_expr(helpers: &Helpers, name: &str, span: Span) -> ModuleItem {
+    let expr = match helpers.format {
+        ModuleFormat::Async => create_call(helpers.expr(&helpers.names.exports_all), vec![
+            ExprOrSpread { spread: Take::dummy(), expr: helpers.expr(name)},
+        ]),
+        ModuleFormat::CommonJs => create_export_all_expr(helpers, name),
+    };
     ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+        span,
+        expr,
+    }))
+}
+
+// Object.keys(__ice_import_0__).forEach(function(key) {
+//     if (key === "default" || Object.prototype.hasOwnProperty.call(module.exports, key)) return;
+//     Object.defineProperty(module.exports, key, { enumerable: true, get() { return __ice_import_0__[key]; } });
+// })
+fn create_export_all_expr(helpers: &Helpers, name: &str) -> Box<Expr> {
+    let key = helpers.ident("key");
+    let key_expr = || Box::new(Expr::Ident(key.clone()));
+    let has_own_property = create_member_expr(
+        create_member_expr(create_member_expr(create_ident_expr("Object"), "prototype"), "hasOwnProperty"),
+        "call",
+    );
+    let skip = Stmt::If(IfStmt {
         span: DUMMY_SP,
-        expr: Box::new(Expr::Call(
-            CallExpr {
+        test: Box::new(Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: op!("||"),
+            left: Box::new(Expr::Bin(BinExpr {
                 span: DUMMY_SP,
-                callee: Callee::Expr(Box::new(Expr::Ident(Ident { ctxt: SyntaxContext::empty(), span: DUMMY_SP, sym: JsWord::from("__ice_exports_all__"), optional: Default::default() }))),
-                args: vec![
-                    ExprOrSpread { spread: Take::dummy(), expr: Box::new(Expr::Ident(Ident {ctxt: SyntaxContext::empty(), span: DUMMY_SP, sym: JsWord::from(name), optional: Default::default() }))},
-                ],
-                type_args: Take::dummy(),
-                ctxt: SyntaxContext::empty()
-            }
-        ))
-    }))
+                op: op!("==="),
+                left: key_expr(),
+                right: create_str_expr("default"),
+            })),
+            right: create_call(has_own_property, vec![
+                ExprOrSpread { spread: Take::dummy(), expr: helpers.exports_expr() },
+                ExprOrSpread { spread: Take::dummy(), expr: key_expr() },
+            ]),
+        })),
+        cons: Box::new(Stmt::Return(ReturnStmt { span: DUMMY_SP, arg: None })),
+        alt: None,
+    });
+    let value = Box::new(Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: helpers.expr(name),
+        prop: MemberProp::Computed(ComputedPropName { span: DUMMY_SP, expr: key_expr() }),
+    }));
+    let define = Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr: create_define_property(helpers.exports_expr(), key_expr(), value),
+    });
+    let callback = Box::new(Expr::Fn(FnExpr {
+        ident: None,
+        function: Box::new(Function {
+            params: vec![Param {
+                span: DUMMY_SP,
+                decorators: Default::default(),
+                pat: Pat::Ident(BindingIdent { id: key.clone(), type_ann: Default::default() }),
+            }],
+            decorators: Default::default(),
+            span: DUMMY_SP,
+            ctxt: SyntaxContext::empty(),
+            body: Some(BlockStmt { span: DUMMY_SP, ctxt: SyntaxContext::empty(), stmts: vec![skip, define] }),
+            is_generator: false,
+            is_async: false,
+            type_params: Default::default(),
+            return_type: Default::default(),
+        }),
+    }));
+    let keys = create_call(create_member_expr(create_ident_expr("Object"), "keys"), vec![
+        ExprOrSpread { spread: Take::dummy(), expr: helpers.expr(name) },
+    ]);
+    create_call(create_member_expr(keys, "forEach"), vec![
+        ExprOrSpread { spread: Take::dummy(), expr: callback },
+    ])
 }
 
-fn create_default_export(right: Box<Expr>) -> ModuleItem {
+fn create_default_export(helpers: &Helpers, right: Box<Expr>, span: Span) -> ModuleItem {
     ModuleItem::Stmt(Stmt::Expr(ExprStmt {
-        span: DUMMY_SP,
+        span,
         expr: Box::new(Expr::Assign(AssignExpr {
-            span: DUMMY_SP,
+            span,
             left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
                 span: DUMMY_SP,
-                obj: Box::new(Expr::Ident(Ident {
-                    span: DUMMY_SP,
-                    sym: JsWord::from("__ice_exports__"),
-                    optional: false,
-                    ctxt: SyntaxContext::empty(),
-                })),
+                obj: helpers.exports_expr(),
                 prop: MemberProp::Ident(IdentName {
                     span: DUMMY_SP,
                     sym: JsWord::from("default"),
@@ -168,59 +629,348 @@ fn create_default_export(right: Box<Expr>) -> ModuleItem {
     }))
 }
 
+fn create_cjs_export_assignment(right: Box<Expr>, span: Span) -> ModuleItem {
+    ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+        span,
+        expr: Box::new(Expr::Assign(AssignExpr {
+            span,
+            left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+                span: DUMMY_SP,
+                obj: create_ident_expr("module"),
+                prop: MemberProp::Ident(IdentName {
+                    span: DUMMY_SP,
+                    sym: JsWord::from("exports"),
+                }),
+            })),
+            op: op!("="),
+            right,
+        })),
+    }))
+}
+
+// Like the `instantiated` check of TypeScript, a namespace holding only types and ambient
+// declarations is removed by the TS strip pass and has no runtime binding.
+fn is_instantiated_namespace(body: &TsNamespaceBody) -> bool {
+    match body {
+        TsNamespaceBody::TsModuleBlock(block) => block.body.iter().any(|item| match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => is_value_decl(decl),
+            ModuleItem::Stmt(Stmt::Empty(_)) => false,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => is_value_decl(&export_decl.decl),
+            ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals)) => import_equals.is_export,
+            _ => true,
+        }),
+        // namespace A.B {}
+        TsNamespaceBody::TsNamespaceDecl(decl) => !decl.declare && is_instantiated_namespace(&decl.body),
+    }
+}
+
+fn is_value_decl(decl: &Decl) -> bool {
+    match decl {
+        Decl::Class(class_decl) => !class_decl.declare,
+        Decl::Fn(fn_decl) => !fn_decl.declare,
+        Decl::Var(var_decl) => !var_decl.declare,
+        Decl::TsEnum(ts_enum) => !ts_enum.declare,
+        Decl::TsModule(ts_module) => !ts_module.declare && ts_module.body.as_ref().is_some_and(is_instantiated_namespace),
+        _ => false,
+    }
+}
+
 fn get_module_name(export_name: &ModuleExportName) -> &JsWord {
-    let module_name;
     match export_name {
-        ModuleExportName::Ident(ident) => {
-            module_name = &ident.sym;
+        ModuleExportName::Ident(ident) => &ident.sym,
+        ModuleExportName::Str(str) => &str.value,
+    }
+}
+
+type ImportKey = (JsWord, Vec<(JsWord, JsWord)>);
+
+fn get_import_key(src: &Str, with: &Option<Box<ObjectLit>>) -> ImportKey {
+    let mut attributes = vec![];
+    if let Some(with) = with {
+        for prop in with.props.iter() {
+            if let PropOrSpread::Prop(prop) = prop {
+                if let Prop::KeyValue(KeyValueProp { key, value }) = &**prop {
+                    let key = match key {
+                        PropName::Ident(ident) => ident.sym.clone(),
+                        PropName::Str(str) => str.value.clone(),
+                        _ => continue,
+                    };
+                    if let Expr::Lit(Lit::Str(value)) = &**value {
+                        attributes.push((key, value.value.clone()));
+                    }
+                }
+            }
+        }
+    }
+    attributes.sort();
+    (src.value.clone(), attributes)
+}
+
+struct NodeTransform<C: Comments> {
+    config: NodeTransformConfig,
+    context: NodeTransformContext,
+    helpers: Helpers,
+    /// Imported bindings in live bindings mode, mapped to the import object and the property they read.
+    imported_bindings: FxHashMap<Id, (String, JsWord)>,
+    /// Import variables by source and import attributes, so that every import,
+    /// re-export and export-all of the same module shares one `__ice_import__` call.
+    import_vals: FxHashMap<ImportKey, String>,
+    import_id: i32,
+    /// Import calls waiting to be emitted as `Promise.all` batches in parallel imports mode.
+    pending_imports: Vec<ImportCall>,
+    /// Import variables of side effect imports, which are loaded in their own batch.
+    ordered_imports: FxHashSet<String>,
+    module_info: ModuleInfo,
+    /// Imported bindings in HMR mode, mapped to their source and imported name, `None` for namespace imports.
+    hmr_bindings: FxHashMap<Id, (JsWord, Option<JsWord>)>,
+    comments: Option<C>,
+}
+
+impl<C: Comments> NodeTransform<C> {
+    fn get_import_val(&mut self, src: &Str, with: &Option<Box<ObjectLit>>, span: Span, module_items: &mut Vec<ModuleItem>) -> String {
+        let key = get_import_key(src, with);
+        if let Some(import_val) = self.import_vals.get(&key) {
+            return import_val.clone();
+        }
+        let import_val = create_import_str(&self.helpers, self.import_id);
+        self.import_id += 1;
+        let import = ImportCall {
+            import_val: import_val.clone(),
+            src: src.clone(),
+            span,
+            with: with.clone(),
+        };
+        if self.config.parallel_imports && self.config.format == ModuleFormat::Async {
+            self.pending_imports.push(import);
+        } else {
+            module_items.push(create_import_decl(&self.helpers, &import));
+        }
+        self.import_vals.insert(key, import_val.clone());
+        import_val
+    }
+
+    /// Group pending import calls into `Promise.all` batches split by side effect imports.
+    fn create_parallel_import_decls(&mut self) -> Vec<ModuleItem> {
+        let mut module_items = vec![];
+        let mut batch: Vec<ImportCall> = vec![];
+        for import in self.pending_imports.drain(..) {
+            if self.ordered_imports.contains(&import.import_val) {
+                if !batch.is_empty() {
+                    module_items.push(create_parallel_import_decl(&self.helpers, &batch));
+                    batch.clear();
+                }
+                module_items.push(create_import_decl(&self.helpers, &import));
+            } else {
+                batch.push(import);
+            }
         }
-        ModuleExportName::Str(str) => {
-            module_name = &str.value;
+        match batch.len() {
+            0 => {}
+            1 => module_items.push(create_import_decl(&self.helpers, &batch[0])),
+            _ => module_items.push(create_parallel_import_decl(&self.helpers, &batch)),
         }
+        module_items
+    }
+
+    /// Value of `import.meta.<property>` which is known at compile time.
+    fn get_import_meta_value(&self, property: &str) -> Option<String> {
+        if !self.config.inline_import_meta {
+            return None;
+        }
+        let filename = self.context.filename.as_ref()?;
+        match property {
+            "url" => Some(create_file_url(filename)),
+            "filename" => Some(filename.clone()),
+            "dirname" => Path::new(filename).parent().map(|dirname| dirname.to_string_lossy().to_string()),
+            _ => None,
+        }
+    }
+
+    fn init_helpers_ctxt(&mut self) {
+        if self.helpers.ctxt == SyntaxContext::empty() {
+            self.helpers.ctxt = SyntaxContext::empty().apply_mark(Mark::new());
+        }
+    }
+
+    fn add_module_import(&mut self, src: &Str, specifiers: Vec<String>) {
+        let source = src.value.to_string();
+        match self.module_info.imports.iter_mut().find(|import| import.source == source) {
+            Some(import) => {
+                for specifier in specifiers {
+                    if !import.specifiers.contains(&specifier) {
+                        import.specifiers.push(specifier);
+                    }
+                }
+            }
+            None => self.module_info.imports.push(ModuleImport { source, specifiers, used: self.config.hmr.then(Vec::new) }),
+        }
+    }
+
+    fn add_used_binding(&mut self, source: &str, name: &str) {
+        if let Some(import) = self.module_info.imports.iter_mut().find(|import| import.source == source) {
+            let used = import.used.get_or_insert_with(Default::default);
+            if !used.iter().any(|used| used == name) {
+                used.push(name.to_string());
+            }
+        }
+    }
+
+    /// Record a read of an imported binding in HMR mode, `property` is the name read from a namespace import.
+    fn record_binding_use(&mut self, ident: &Ident, property: Option<&str>) {
+        if let Some((source, imported)) = self.hmr_bindings.get(&ident.to_id()).cloned() {
+            self.add_used_binding(&source, imported.as_deref().or(property).unwrap_or("*"));
+        }
+    }
+
+    /// Record `ns.foo` of a namespace import `ns` as a read of `foo`.
+    fn record_namespace_member(&mut self, member: &MemberExpr) -> bool {
+        let Expr::Ident(obj) = &*member.obj else {
+            return false;
+        };
+        if !matches!(self.hmr_bindings.get(&obj.to_id()), Some((_, None))) {
+            return false;
+        }
+        let property = match &member.prop {
+            MemberProp::Ident(prop) => Some(prop.sym.clone()),
+            MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                Expr::Lit(Lit::Str(str)) => Some(str.value.clone()),
+                _ => None,
+            },
+            MemberProp::PrivateName(_) => None,
+        };
+        self.record_binding_use(obj, property.as_deref());
+        true
+    }
+
+    fn add_module_export(&mut self, name: &str) {
+        if !self.module_info.exports.iter().any(|export| export == name) {
+            self.module_info.exports.push(name.to_string());
+        }
+    }
+
+    fn get_imported_binding(&self, ident: &Ident) -> Option<&(String, JsWord)> {
+        if self.imported_bindings.is_empty() {
+            return None;
+        }
+        self.imported_bindings.get(&ident.to_id())
+    }
+
+    /// Fold the callee of a call or the tag of a tagged template. An imported binding is read as
+    /// `(0, __ice_import_0__.a)`, so that it is called with an undefined `this` like the ESM binding.
+    fn fold_callee_expr(&mut self, callee: Box<Expr>) -> Box<Expr> {
+        if let Expr::Ident(ident) = &*callee {
+            if let Some((import_val, property)) = self.get_imported_binding(ident).cloned() {
+                self.record_binding_use(ident, None);
+                let member = create_member_expr_with_span(self.helpers.expr(&import_val), &property, ident.span);
+                return Box::new(Expr::Paren(ParenExpr {
+                    span: ident.span,
+                    expr: Box::new(Expr::Seq(SeqExpr {
+                        span: ident.span,
+                        exprs: vec![
+                            Box::new(Expr::Lit(Lit::Num(Number { span: DUMMY_SP, value: 0.0, raw: None }))),
+                            member,
+                        ],
+                    })),
+                }));
+            }
+        }
+        callee.fold_with(self)
     }
-    module_name
 }
 
-struct NodeTransform;
+impl<C: Comments> Fold for NodeTransform<C> {
+    fn fold_script(&mut self, script: Script) -> Script {
+        self.init_helpers_ctxt();
+        script.fold_children_with(self)
+    }
+
+    fn fold_module(&mut self, module: Module) -> Module {
+        self.init_helpers_ctxt();
+        let module = module.fold_children_with(self);
+        if let Some(comments) = &self.comments {
+            // Names and sources may contain `*/`, which would close the comment.
+            let module_info = serde_json::to_string(&self.module_info)
+                .expect("failed to serialize module info")
+                .replace("*/", "*\\u002f");
+            comments.add_leading(module.span.lo, Comment {
+                kind: CommentKind::Block,
+                span: DUMMY_SP,
+                text: format!("{} {}", MODULE_INFO_COMMENT, module_info).into(),
+            });
+        }
+        module
+    }
 
-impl Fold for NodeTransform {
     fn fold_module_items(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
         let mut new_module_items: Vec<ModuleItem> = vec![];
-        let mut import_id:i32 = 0;
+        let mut hoisted_exports: Vec<ModuleItem> = vec![];
         for module_item in items.iter() {
             match module_item {
+                // Type-only imports and exports have no runtime value.
+                ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl { type_only: true, .. }))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { type_only: true, .. }))
+                | ModuleItem::ModuleDecl(ModuleDecl::TsNamespaceExport(_)) => {}
+                ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals)) if import_equals.is_type_only => {}
                 ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
                     // Check all import statement and replace with custom import function `__ice_import__`
-                    let import_val = format!("__ice_import_{}__", import_id);
-                    import_id += 1;
                     // import foo from 'test' -> const __ice_import_0__ = await __ice_import__("test");
-                    new_module_items.push(create_import_decl(&import_val, &import_decl.src.value));
+                    let import_val = self.get_import_val(&import_decl.src, &import_decl.with, import_decl.span, &mut new_module_items);
+                    if import_decl.specifiers.is_empty() {
+                        self.ordered_imports.insert(import_val.clone());
+                    }
+                    let value_specifiers: Vec<&ImportSpecifier> = import_decl.specifiers.iter()
+                        .filter(|specifier| !matches!(specifier, ImportSpecifier::Named(ImportNamedSpecifier { is_type_only: true, .. })))
+                        .collect();
+                    let specifiers: Vec<String> = value_specifiers.iter().map(|specifier| match specifier {
+                        ImportSpecifier::Named(ImportNamedSpecifier { imported: Some(imported), .. }) => get_module_name(imported).to_string(),
+                        ImportSpecifier::Named(ImportNamedSpecifier { local, .. }) => local.sym.to_string(),
+                        ImportSpecifier::Default(_) => String::from("default"),
+                        ImportSpecifier::Namespace(_) => String::from("*"),
+                    }).collect();
+                    if self.config.hmr {
+                        for (specifier, imported) in value_specifiers.iter().zip(&specifiers) {
+                            let imported = match specifier {
+                                ImportSpecifier::Namespace(_) => None,
+                                _ => Some(JsWord::from(imported.as_str())),
+                            };
+                            self.hmr_bindings.insert(specifier.local().to_id(), (import_decl.src.value.clone(), imported));
+                        }
+                    }
+                    self.add_module_import(&import_decl.src, specifiers);
 
-                    for specifier in import_decl.specifiers.iter() {
+                    for specifier in value_specifiers {
                         match specifier {
                             ImportSpecifier::Named(named) => {
                                 let ImportNamedSpecifier {
-                                    local, imported, ..
+                                    span, local, imported, ..
                                 } = named;
-                                let mut property = &local.sym;
-                                if let Some(ModuleExportName::Ident(import_ident)) = imported {
-                                    property = &import_ident.sym;
+                                let property = match imported {
+                                    Some(imported) => get_module_name(imported),
+                                    None => &local.sym,
+                                };
+                                // Names like `"foo-bar"` can not be read in JSX element names,
+                                // so they are still copied into a local binding.
+                                // The default of `require` is not a property read, so it is not a live binding.
+                                let is_cjs_default = self.config.format == ModuleFormat::CommonJs && &**property == "default";
+                                if self.config.live_bindings && is_valid_prop_name(property) && !is_cjs_default {
+                                    self.imported_bindings.insert(local.to_id(), (import_val.clone(), property.clone()));
+                                    continue;
                                 }
                                 new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
-                                    span: DUMMY_SP,
+                                    span: *span,
                                     kind: VarDeclKind::Const,
                                     declare: false,
-                                    decls: vec![create_member_decl(local.clone(), &import_val, property)],
+                                    decls: vec![create_member_decl(local.clone(), &self.helpers, &import_val, property)],
                                     ctxt: SyntaxContext::empty(),
                                 })))))
                             }
                             ImportSpecifier::Namespace(namespace) => {
                                 let ImportStarAsSpecifier {
-                                    local, ..
+                                    span, local, ..
                                 } = namespace;
                                 new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
                                     ctxt: SyntaxContext::empty(),
-                                    span: DUMMY_SP,
+                                    span: *span,
                                     kind: VarDeclKind::Const,
                                     declare: false,
                                     decls: vec![
@@ -230,21 +980,23 @@ impl Fold for NodeTransform {
                                                 id: local.clone(),
                                                 type_ann: Default::default(),
                                             }),
-                                            init: Option::Some(Box::new(Expr::Ident(
-                                                Ident { ctxt: SyntaxContext::empty(),  span: DUMMY_SP, sym: JsWord::from(import_val.to_string()), optional: Default::default() }
-                                        ))), definite: false }
+                                            init: Option::Some(self.helpers.expr(&import_val)), definite: false }
                                     ],
                                 })))))
                             }
                             ImportSpecifier::Default(default) => {
                                 let ImportDefaultSpecifier {
-                                    local, ..
+                                    span, local, ..
                                 } = default;
+                                if self.config.live_bindings && self.config.format == ModuleFormat::Async {
+                                    self.imported_bindings.insert(local.to_id(), (import_val.clone(), JsWord::from("default")));
+                                    continue;
+                                }
                                 new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
-                                    span: DUMMY_SP,
+                                    span: *span,
                                     kind: VarDeclKind::Const,
                                     declare: false,
-                                    decls: vec![create_member_decl(local.clone(), &import_val, "default")],
+                                    decls: vec![create_member_decl(local.clone(), &self.helpers, &import_val, "default")],
                                     ctxt: SyntaxContext::empty()
                                 })))))
                             }
@@ -253,48 +1005,63 @@ impl Fold for NodeTransform {
                     
                 }
                 ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named)) => {
-                    let import_val = create_import_str(import_id);
-                    let mut has_import = false;
+                    // Replace export statement with custom import function `__ice_import__`
+                    let import_val = export_named.src.as_ref().map(|src| {
+                        self.get_import_val(src, &export_named.with, export_named.span, &mut new_module_items)
+                    });
                     if let Some(src) = &export_named.src {
-                        // Replace export statement with custom import function `__ice_import__`
-                        has_import = true;
-                        import_id += 1;
-                        new_module_items.push(create_import_decl(&import_val, &src.value));
+                        let specifiers = export_named.specifiers.iter().filter_map(|specifier| match specifier {
+                            ExportSpecifier::Named(named) if named.is_type_only => None,
+                            ExportSpecifier::Named(named) => Some(get_module_name(&named.orig).to_string()),
+                            ExportSpecifier::Namespace(_) => Some(String::from("*")),
+                            ExportSpecifier::Default(_) => None,
+                        }).collect::<Vec<_>>();
+                        self.add_module_import(src, specifiers.clone());
+                        // Re-exports are read by the getters of the exports.
+                        if self.config.hmr {
+                            for specifier in specifiers {
+                                self.add_used_binding(&src.value, &specifier);
+                            }
+                        }
                     }
                     
                     for specifier in export_named.specifiers.iter() {
                         match specifier {
+                            ExportSpecifier::Named(named) if named.is_type_only => {}
                             ExportSpecifier::Named(named) => {
                                 let ExportNamedSpecifier {
-                                    orig, exported, ..
+                                    span, orig, exported, ..
                                 } = named;
-                                let orig_name = get_module_name(&orig);
-                                let export_name;
-                                let return_value;
-                                
-                                if let Some(exported_ident) = exported {
-                                    export_name = get_module_name(exported_ident);
+                                let orig_name = get_module_name(orig);
+                                let export_name = if let Some(exported_ident) = exported {
+                                    get_module_name(exported_ident)
                                 } else {
-                                    export_name = orig_name;
-                                }
+                                    orig_name
+                                };
+                                self.add_module_export(export_name);
                                 // Concat with import value if import value is not empty
-                                if has_import {
-                                    return_value = format!("{}.{}", import_val, orig_name);
+                                if let Some(import_val) = &import_val {
+                                    let return_value = self.helpers.import_member(import_val, orig_name);
+                                    new_module_items.push(create_define_export(&self.helpers, export_name, return_value, *span));
                                 } else {
-                                    return_value = orig_name.to_string();
+                                    let return_value = if let ModuleExportName::Ident(orig_ident) = orig {
+                                        Box::new(Expr::Ident(orig_ident.clone()))
+                                    } else {
+                                        create_ident_expr(orig_name)
+                                    };
+                                    hoisted_exports.push(create_define_export(&self.helpers, export_name, return_value, *span));
                                 }
-                                // Convert return value to &str
-                                new_module_items.push(create_define_export(export_name, return_value.as_str()));   
                             }
                             ExportSpecifier::Namespace(default) => {
                                 let ExportNamespaceSpecifier {
-                                    name, ..
+                                    span, name,
                                 } = default;
-                                let export_name = get_module_name(&name);
-                                if has_import {
-                                    new_module_items.push(create_define_export(export_name, &import_val));
+                                let export_name = get_module_name(name);
+                                self.add_module_export(export_name);
+                                if let Some(import_val) = &import_val {
+                                    new_module_items.push(create_define_export(&self.helpers, export_name, self.helpers.expr(import_val), *span));
                                 } else {
-                                    new_module_items.push(create_define_export(export_name, &export_name));
+                                    new_module_items.push(create_define_export(&self.helpers, export_name, create_ident_expr(export_name), *span));
                                 }
                             }
                             _ => {}
@@ -303,122 +1070,354 @@ impl Fold for NodeTransform {
                     
                 }
                 ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+                    if !matches!(export_default_decl.decl, DefaultDecl::TsInterfaceDecl(_)) {
+                        self.add_module_export("default");
+                    }
                     match &export_default_decl.decl {
                         DefaultDecl::Class(class_decl) => {
                             if let Some(ident) = &class_decl.ident {
-                                let export_name = &ident.sym;
                                 new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Class(ClassDecl {
                                     ident: ident.clone(),
                                     declare: false,
                                     class: class_decl.class.clone()
                                 }))));
-                                new_module_items.push(create_define_export("default", export_name));
+                                hoisted_exports.push(create_define_export(&self.helpers, "default", Box::new(Expr::Ident(ident.clone())), export_default_decl.span));
                             } else {
-                                new_module_items.push(create_default_export(Box::new(Expr::Class(class_decl.clone()))));
+                                // export default class {} -> class _default {}, named `default` like in ESM
+                                let binding = self.helpers.ident("_default");
+                                new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Class(ClassDecl {
+                                    ident: binding.clone(),
+                                    declare: false,
+                                    class: class_decl.class.clone()
+                                }))));
+                                if !has_static_name(&class_decl.class) {
+                                    new_module_items.push(create_default_name(&binding));
+                                }
+                                hoisted_exports.push(create_define_export(&self.helpers, "default", Box::new(Expr::Ident(binding)), export_default_decl.span));
                             }
                         }
                         DefaultDecl::Fn(function_decl) => {
                             if let Some(ident) = &function_decl.ident {
-                                let export_name = &ident.sym;
                                 new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl {
                                     ident: ident.clone(),
                                     function: function_decl.function.clone(),
                                     declare: false,
                                 }))));
-                                new_module_items.push(create_define_export("default", export_name));
+                                hoisted_exports.push(create_define_export(&self.helpers, "default", Box::new(Expr::Ident(ident.clone())), export_default_decl.span));
                             } else {
-                                // new_module_items.push(module_item.clone())
-                                new_module_items.push(create_default_export(Box::new(Expr::Fn(function_decl.clone()))));
+                                // export default function () {} -> function _default() {}, which is hoisted
+                                // like the anonymous function declaration.
+                                let binding = self.helpers.ident("_default");
+                                new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl {
+                                    ident: binding.clone(),
+                                    function: function_decl.function.clone(),
+                                    declare: false,
+                                }))));
+                                hoisted_exports.push(create_define_export(&self.helpers, "default", Box::new(Expr::Ident(binding.clone())), export_default_decl.span));
+                                hoisted_exports.push(create_default_name(&binding));
                             }
                         }
-                        _ => {
-                            new_module_items.push(module_item.clone())
-                        }
+                        // export default interface Foo {} has no runtime value.
+                        DefaultDecl::TsInterfaceDecl(_) => {}
                     }
                 }
                 ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default_expr)) => {
-                    // Create member expression of `__ice_exports__.default = <export_default_expr>;`
-                    new_module_items.push(create_default_export(export_default_expr.expr.clone()));
+                    self.add_module_export("default");
+                    let anonymous = match get_anonymous_default(&export_default_expr.expr) {
+                        Some(Expr::Class(class_expr)) => Some(!has_static_name(&class_expr.class)),
+                        Some(_) => Some(true),
+                        None => None,
+                    };
+                    if let Some(set_name) = anonymous {
+                        // export default () => {} -> const _default = () => {}, named `default` like in ESM
+                        let binding = self.helpers.ident("_default");
+                        new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                            span: export_default_expr.span,
+                            kind: VarDeclKind::Const,
+                            declare: false,
+                            decls: vec![create_var_decl(binding.clone(), Some(export_default_expr.expr.clone()))],
+                            ctxt: SyntaxContext::empty(),
+                        })))));
+                        if set_name {
+                            new_module_items.push(create_default_name(&binding));
+                        }
+                        hoisted_exports.push(create_define_export(&self.helpers, "default", Box::new(Expr::Ident(binding)), export_default_expr.span));
+                    } else {
+                        // Create member expression of `__ice_exports__.default = <export_default_expr>;`
+                        new_module_items.push(create_default_export(&self.helpers, export_default_expr.expr.clone(), export_default_expr.span));
+                    }
                 }
                 ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export_all)) => {
-                    let import_val = create_import_str(import_id);
-                    import_id += 1;
-                    new_module_items.push(create_import_decl(&import_val, &export_all.src.value));
-                    new_module_items.push(create_call_expr(&import_val));
+                    let import_val = self.get_import_val(&export_all.src, &export_all.with, export_all.span, &mut new_module_items);
+                    self.add_module_import(&export_all.src, vec![]);
+                    if self.config.hmr {
+                        self.add_used_binding(&export_all.src.value, "*");
+                    }
+                    self.module_info.export_all.push(export_all.src.value.to_string());
+                    new_module_items.push(create_call_expr(&self.helpers, &import_val, export_all.span));
 
                 }
-                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => { 
-                    match &export_decl.decl {
-                        Decl::Class(class_decl) => {
-                            let class_name = &class_decl.ident.sym;
-                            new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl.clone()))));
-                            new_module_items.push(create_define_export(class_name, class_name));
-                        }
-                        Decl::Fn(fn_decl) => {
-                            let fn_name = &fn_decl.ident.sym;
-                            new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl.clone()))));
-                            new_module_items.push(create_define_export(fn_name, fn_name));
-                        }
-                        Decl::Var(var_decl) => {
-                            new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl.clone()))));
-                            for decl in var_decl.decls.iter() {
-                                if decl.name.is_ident() {
-                                    let var_name = &decl.name.as_ident().unwrap().id.sym;
-                                    new_module_items.push(create_define_export(var_name, var_name));
-                                }
-                            }
-                        }
-                        _ => {}
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                    new_module_items.push(ModuleItem::Stmt(Stmt::Decl(export_decl.decl.clone())));
+                    // Ambient declarations and types have no runtime binding to export.
+                    let export_idents = match &export_decl.decl {
+                        Decl::Class(class_decl) if !class_decl.declare => vec![class_decl.ident.clone()],
+                        Decl::Fn(fn_decl) if !fn_decl.declare => vec![fn_decl.ident.clone()],
+                        // export const { a, b: [c] } = obj -> export a and c
+                        Decl::Var(var_decl) if !var_decl.declare => var_decl.decls.iter()
+                            .flat_map(|decl| find_pat_ids::<_, Ident>(&decl.name))
+                            .collect(),
+                        Decl::TsEnum(ts_enum) if !ts_enum.declare => vec![ts_enum.id.clone()],
+                        Decl::TsModule(ts_module) if !ts_module.declare && ts_module.body.as_ref().is_some_and(is_instantiated_namespace) => match &ts_module.id {
+                            TsModuleName::Ident(id) => vec![id.clone()],
+                            TsModuleName::Str(_) => vec![],
+                        },
+                        _ => vec![],
+                    };
+                    for ident in export_idents {
+                        let export_name = ident.sym.clone();
+                        self.add_module_export(&export_name);
+                        hoisted_exports.push(create_define_export(&self.helpers, &export_name, Box::new(Expr::Ident(ident)), export_decl.span));
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals)) => {
+                    let TsImportEqualsDecl { span, is_export, id, module_ref, .. } = &**import_equals;
+                    // `import foo = Utils.foo` may alias a type, it is left to the TS strip pass.
+                    let TsModuleRef::TsExternalModuleRef(TsExternalModuleRef { expr: src, .. }) = module_ref else {
+                        new_module_items.push(module_item.clone());
+                        continue;
+                    };
+                    // import foo = require('test') -> const foo = __ice_import_0__.default;
+                    // `export = foo` is the default export, like `module.exports` of CommonJS modules
+                    // imported by node. `require` returns it as is.
+                    let import_val = self.get_import_val(src, &None, *span, &mut new_module_items);
+                    self.add_module_import(src, vec![String::from("default")]);
+                    if self.config.hmr {
+                        self.hmr_bindings.insert(id.to_id(), (src.value.clone(), Some(JsWord::from("default"))));
+                    }
+                    let init = match self.config.format {
+                        ModuleFormat::Async => create_member_expr(self.helpers.expr(&import_val), "default"),
+                        ModuleFormat::CommonJs => self.helpers.expr(&import_val),
+                    };
+                    new_module_items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                        span: *span,
+                        kind: VarDeclKind::Const,
+                        declare: false,
+                        decls: vec![create_var_decl(id.clone(), Some(init))],
+                        ctxt: SyntaxContext::empty(),
+                    })))));
+                    if *is_export {
+                        self.add_module_export(&id.sym);
+                        hoisted_exports.push(create_define_export(&self.helpers, &id.sym, Box::new(Expr::Ident(id.clone())), *span));
                     }
                 }
+                ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(export_assignment)) => {
+                    // export = foo -> __ice_exports__.default = foo, or module.exports = foo for CommonJS.
+                    self.add_module_export("default");
+                    let item = match self.config.format {
+                        ModuleFormat::Async => create_default_export(&self.helpers, export_assignment.expr.clone(), export_assignment.span),
+                        ModuleFormat::CommonJs => create_cjs_export_assignment(export_assignment.expr.clone(), export_assignment.span),
+                    };
+                    new_module_items.push(item);
+                }
                 _ => {
                     new_module_items.push(module_item.clone())
                 }
             }
         }
+        if !self.pending_imports.is_empty() {
+            // Imports are hoisted, so all import calls start before the module body.
+            let mut import_decls = self.create_parallel_import_decls();
+            import_decls.append(&mut new_module_items);
+            new_module_items = import_decls;
+        }
+        // Exports of local bindings are defined before the first import is awaited, so that
+        // modules in an import cycle see them like ESM bindings (reading a `let`, `const` or
+        // class before its declaration still throws).
+        hoisted_exports.append(&mut new_module_items);
+        new_module_items = hoisted_exports;
+        let has_exports = !self.module_info.exports.is_empty() || !self.module_info.export_all.is_empty();
+        // `export = foo` replaces `module.exports`, like a CommonJS module.
+        let has_export_assignment = items.iter().any(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(_))));
+        if self.config.format == ModuleFormat::CommonJs && has_exports && !has_export_assignment {
+            new_module_items.insert(0, create_es_module_flag(&self.helpers));
+        }
         new_module_items = new_module_items.fold_children_with(self);
         new_module_items
     }
 
+    fn fold_ts_module_block(&mut self, block: TsModuleBlock) -> TsModuleBlock {
+        // Exports of a namespace are members of the namespace, not of the module.
+        TsModuleBlock {
+            body: block.body.into_iter().map(|item| item.fold_with(self)).collect(),
+            ..block
+        }
+    }
+
     fn fold_call_expr(&mut self, call_expr: CallExpr) -> CallExpr {
         let callee = &call_expr.callee;
-        if let Callee::Import(_) = callee {
+        if let (Callee::Import(_), ModuleFormat::CommonJs) = (callee, self.config.format) {
+            // import(src) -> Promise.resolve(src).then(require)
+            let args = call_expr.args.fold_with(self).into_iter().take(1).collect();
+            CallExpr {
+                span: call_expr.span,
+                args: vec![ExprOrSpread { spread: Take::dummy(), expr: create_ident_expr("require") }],
+                type_args: None,
+                callee: Callee::Expr(create_member_expr(
+                    create_call(create_member_expr(create_ident_expr("Promise"), "resolve"), args),
+                    "then",
+                )),
+                ctxt: SyntaxContext::empty(),
+            }
+        } else if let Callee::Import(import) = callee {
+            // The options of `import(src, { with: { type: 'json' } })` are passed through as the second argument.
+            let callee = Ident { span: import.span, ..self.helpers.ident(&self.helpers.names.dynamic_import) };
             CallExpr {
-                span: call_expr.span.clone(),
-                args: call_expr.args.clone(),
+                span: call_expr.span,
+                args: call_expr.args.fold_with(self),
                 type_args: call_expr.type_args.clone(),
-                callee: Callee::Expr(Box::new(Expr::Ident(Ident {
-                    span: DUMMY_SP,
-                    sym: JsWord::from("__ice_dynamic_import__"),
-                    optional: Default::default(),
-                    ctxt: SyntaxContext::empty()
-                }))),
+                callee: Callee::Expr(Box::new(Expr::Ident(callee))),
                 ctxt: SyntaxContext::empty(),
             }
+        } else if let Callee::Expr(callee) = call_expr.callee {
+            CallExpr {
+                callee: Callee::Expr(self.fold_callee_expr(callee)),
+                args: call_expr.args.fold_with(self),
+                type_args: call_expr.type_args.fold_with(self),
+                ..call_expr
+            }
         } else {
             call_expr.fold_children_with(self)
         }
     }
-    fn fold_member_expr(&mut self, member_expr:MemberExpr) -> MemberExpr {
-        if member_expr.obj.is_meta_prop() && member_expr.obj.as_meta_prop().unwrap().kind == MetaPropKind::ImportMeta {
-            // Replace `import.meta` with `__ice_import_meta__`
-            MemberExpr {
-                span: member_expr.span.clone(),
-                obj: Box::new(Expr::Ident(Ident {
-                    span: DUMMY_SP,
-                    sym: JsWord::from("__ice_import_meta__"),
-                    optional: Default::default(),
-                    ctxt: SyntaxContext::empty()
-                })),
-                prop: member_expr.prop,
+
+    fn fold_tagged_tpl(&mut self, tagged_tpl: TaggedTpl) -> TaggedTpl {
+        TaggedTpl {
+            tag: self.fold_callee_expr(tagged_tpl.tag),
+            type_params: tagged_tpl.type_params.fold_with(self),
+            tpl: tagged_tpl.tpl.fold_with(self),
+            ..tagged_tpl
+        }
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match &expr {
+            Expr::Ident(ident) => {
+                self.record_binding_use(ident, None);
+                // Replace imported binding `a` with `__ice_import_0__.a`
+                if let Some((import_val, property)) = self.get_imported_binding(ident) {
+                    return *create_member_expr_with_span(self.helpers.expr(import_val), property, ident.span);
+                }
+            }
+            Expr::MetaProp(MetaPropExpr { span, kind: MetaPropKind::ImportMeta }) => {
+                if self.config.format == ModuleFormat::CommonJs {
+                    return create_cjs_import_meta(*span);
+                }
+                // Replace `import.meta` with `__ice_import_meta__`
+                return Expr::Ident(Ident {
+                    span: *span,
+                    ..self.helpers.ident(&self.helpers.names.import_meta)
+                });
+            }
+            Expr::Member(MemberExpr { span, obj, prop: MemberProp::Ident(prop) }) => {
+                // Replace `import.meta.url` with `"file:///path/to/module.js"`
+                if let Expr::MetaProp(MetaPropExpr { kind: MetaPropKind::ImportMeta, .. }) = &**obj {
+                    // Replace `import.meta.hot` with `__ice_hot__`
+                    if self.config.hmr && &*prop.sym == "hot" {
+                        return Expr::Ident(Ident {
+                            span: *span,
+                            ..self.helpers.ident(&self.helpers.names.hot)
+                        });
+                    }
+                    if let Some(value) = self.get_import_meta_value(&prop.sym) {
+                        return Expr::Lit(Lit::Str(Str {
+                            span: *span,
+                            value: JsWord::from(value),
+                            raw: Default::default(),
+                        }));
+                    }
+                    if self.config.format == ModuleFormat::CommonJs {
+                        if let Some(value) = create_cjs_import_meta_value(&prop.sym) {
+                            return *value;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        expr.fold_children_with(self)
+    }
+
+    fn fold_member_expr(&mut self, member: MemberExpr) -> MemberExpr {
+        // Namespace imports are never live bindings, so only the property needs folding.
+        if self.record_namespace_member(&member) {
+            return MemberExpr {
+                prop: member.prop.fold_with(self),
+                ..member
+            };
+        }
+        member.fold_children_with(self)
+    }
+
+    fn fold_prop(&mut self, prop: Prop) -> Prop {
+        if let Prop::Shorthand(ident) = &prop {
+            self.record_binding_use(ident, None);
+            // Expand `{ a }` to `{ a: __ice_import_0__.a }`
+            if let Some((import_val, property)) = self.get_imported_binding(ident) {
+                return Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(IdentName { span: ident.span, sym: ident.sym.clone() }),
+                    value: create_member_expr_with_span(self.helpers.expr(import_val), property, ident.span),
+                });
             }
-        } else {
-            member_expr.fold_children_with(self)
         }
+        prop.fold_children_with(self)
+    }
+
+    fn fold_jsx_element_name(&mut self, name: JSXElementName) -> JSXElementName {
+        match &name {
+            JSXElementName::Ident(ident) => self.record_binding_use(ident, None),
+            JSXElementName::JSXMemberExpr(JSXMemberExpr { obj: JSXObject::Ident(obj), prop, .. }) => {
+                self.record_binding_use(obj, Some(&prop.sym));
+            }
+            _ => {}
+        }
+        if let JSXElementName::Ident(ident) = &name {
+            // Replace `<A />` with `<__ice_import_0__.A />`
+            if let Some((import_val, property)) = self.get_imported_binding(ident) {
+                return JSXElementName::JSXMemberExpr(JSXMemberExpr {
+                    span: ident.span,
+                    obj: JSXObject::Ident(self.helpers.ident(import_val)),
+                    prop: IdentName { span: DUMMY_SP, sym: property.clone() },
+                });
+            }
+        }
+        name.fold_children_with(self)
+    }
+
+    fn fold_jsx_object(&mut self, obj: JSXObject) -> JSXObject {
+        if let JSXObject::Ident(ident) = &obj {
+            // Replace `<A.Item />` with `<__ice_import_0__.A.Item />`
+            if let Some((import_val, property)) = self.get_imported_binding(ident) {
+                return JSXObject::JSXMemberExpr(Box::new(JSXMemberExpr {
+                    span: ident.span,
+                    obj: JSXObject::Ident(self.helpers.ident(import_val)),
+                    prop: IdentName { span: DUMMY_SP, sym: property.clone() },
+                }));
+            }
+        }
+        obj.fold_children_with(self)
     }
 }
 
 #[plugin_transform]
 pub fn process_transform(program: Program, _metadata: TransformPluginProgramMetadata) -> Program {
-    program.fold_with(&mut node_transform())
+    let config = _metadata
+        .get_transform_plugin_config()
+        .map(|config| serde_json::from_str::<NodeTransformConfig>(&config).expect("invalid config for node-transform"))
+        .unwrap_or_default();
+    let context = NodeTransformContext {
+        filename: _metadata.get_context(&TransformPluginMetadataContextKind::Filename),
+    };
+
+    program.fold_with(&mut node_transform_with_context(config, context, _metadata.comments))
 }
\ No newline at end of file
@@ -1,19 +1,100 @@
-use std::path::PathBuf;
+use std::{fs, path::{Path, PathBuf}, process::Command};
 use swc_core::{
-  ecma::transforms::testing::{test_fixture, FixtureTestConfig},
+  common::{comments::NoopComments, chain, Mark, SourceMapper, Span, Spanned},
+  ecma::{
+    ast::{CallExpr, Callee, Decl, Expr, ExprStmt, ModuleItem, Pat, Stmt},
+    parser::{EsSyntax, Syntax, TsSyntax},
+    transforms::{
+      base::{fixer::fixer, hygiene::hygiene, resolver},
+      testing::{test_fixture, FixtureTestConfig, Tester},
+    },
+    visit::FoldWith,
+  },
 };
 use testing::fixture;
-use swc_plugin_node_transform::{node_transform};
+use swc_plugin_node_transform::{
+  node_transform, node_transform_with_context, ModuleFormat, NodeTransformConfig, NodeTransformContext,
+  RuntimeHelpers,
+};
 
-#[fixture("tests/fixture/**/input.js")]
+#[fixture("tests/fixture/**/input.js", exclude("fixture/(live-bindings|parallel-imports|helpers|inline-import-meta|commonjs|module-info|hmr)/"))]
 fn fixture(input: PathBuf) {
   let parent = input.parent().unwrap();
   let output = parent.join("output.js");
 
+  test_fixture(
+    Syntax::Es(EsSyntax {
+      import_attributes: true,
+      ..Default::default()
+    }),
+    &|_t| {
+      node_transform(Default::default())
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/ts-*/input.ts", exclude("fixture/ts-commonjs/"))]
+fn fixture_ts(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.ts");
+
+  test_fixture(
+    Syntax::Typescript(TsSyntax::default()),
+    &|_t| {
+      node_transform(Default::default())
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/live-bindings/**/input.js")]
+fn fixture_live_bindings(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Syntax::Es(EsSyntax {
+      jsx: true,
+      ..Default::default()
+    }),
+    &|_t| {
+      chain!(
+        resolver(Mark::new(), Mark::new(), false),
+        node_transform(NodeTransformConfig {
+          live_bindings: true,
+          ..Default::default()
+        })
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/parallel-imports/input.js")]
+fn fixture_parallel_imports(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
   test_fixture(
     Default::default(),
     &|_t| {
-      node_transform()
+      node_transform(NodeTransformConfig {
+        parallel_imports: true,
+        ..Default::default()
+      })
     },
     &input,
     &output,
@@ -22,3 +103,354 @@ fn fixture(input: PathBuf) {
     },
   );
 }
+#[fixture("tests/fixture/helpers/input.js")]
+fn fixture_helpers(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      node_transform(NodeTransformConfig {
+        helpers: RuntimeHelpers {
+          import: String::from("__vite_ssr_import__"),
+          dynamic_import: String::from("__vite_ssr_dynamic_import__"),
+          exports: String::from("__vite_ssr_exports__"),
+          exports_all: String::from("__vite_ssr_exportAll__"),
+          import_meta: String::from("__vite_ssr_import_meta__"),
+          import_var: String::from("__vite_ssr_import_{}__"),
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+#[fixture("tests/fixture/inline-import-meta/input.js")]
+fn fixture_inline_import_meta(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      node_transform_with_context(
+        NodeTransformConfig {
+          inline_import_meta: true,
+          ..Default::default()
+        },
+        NodeTransformContext {
+          filename: Some(String::from("/project/src/pages/about us.js")),
+        },
+        None::<NoopComments>,
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+#[fixture("tests/fixture/commonjs/input.js")]
+fn fixture_commonjs(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      node_transform(NodeTransformConfig {
+        format: ModuleFormat::CommonJs,
+        ..Default::default()
+      })
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/module-info/**/input.js")]
+fn fixture_module_info(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Default::default(),
+    &|t| {
+      node_transform_with_context(Default::default(), Default::default(), Some(t.comments.clone()))
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/ts-commonjs/input.ts")]
+fn fixture_ts_commonjs(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.ts");
+
+  test_fixture(
+    Syntax::Typescript(TsSyntax::default()),
+    &|_t| {
+      node_transform(NodeTransformConfig {
+        format: ModuleFormat::CommonJs,
+        ..Default::default()
+      })
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[fixture("tests/fixture/hmr/input.js")]
+fn fixture_hmr(input: PathBuf) {
+  let parent = input.parent().unwrap();
+  let output = parent.join("output.js");
+
+  test_fixture(
+    Syntax::Es(EsSyntax {
+      jsx: true,
+      ..Default::default()
+    }),
+    &|t| {
+      let unresolved_mark = Mark::new();
+      chain!(
+        resolver(unresolved_mark, Mark::new(), false),
+        node_transform_with_context(
+          NodeTransformConfig {
+            hmr: true,
+            ..Default::default()
+          },
+          Default::default(),
+          Some(t.comments.clone()),
+        )
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+fn transform_module(config: NodeTransformConfig, src: &str) -> String {
+  transform_module_with_syntax(config, Default::default(), src)
+}
+
+fn transform_module_with_syntax(config: NodeTransformConfig, syntax: Syntax, src: &str) -> String {
+  Tester::run(|tester| {
+    let program = tester
+      .apply_transform(node_transform(config), "input.js", syntax, Some(true), src)?
+      .fold_with(&mut hygiene())
+      .fold_with(&mut fixer(None));
+    Ok(tester.print(&program, &Default::default()))
+  })
+}
+
+// Evaluate every module of `dir` with a minimal SSR runtime in node (or a CommonJS loader),
+// starting from `./input`, and print the evaluation log and the exports of the entry. Like the
+// runtime, a circular import resolves to the exports of the pending module.
+fn run_module_graph(dir: &Path, config: NodeTransformConfig) -> String {
+  let factory_params = match config.format {
+    ModuleFormat::Async => "async (__ice_import__, __ice_exports__, __ice_exports_all__)",
+    ModuleFormat::CommonJs => "(require, module)",
+  };
+  let mut factories = String::new();
+  for entry in fs::read_dir(dir).unwrap() {
+    let path = entry.unwrap().path();
+    if path.file_stem().unwrap() == "output" {
+      continue;
+    }
+    let src = fs::read_to_string(&path).unwrap();
+    let syntax = match path.extension().unwrap().to_str() {
+      Some("ts") => Syntax::Typescript(TsSyntax::default()),
+      _ => Default::default(),
+    };
+    factories.push_str(&format!(
+      "\"./{}\": {} => {{\n{}\n}},\n",
+      path.file_stem().unwrap().to_str().unwrap(),
+      factory_params,
+      transform_module_with_syntax(config.clone(), syntax, &src),
+    ));
+  }
+  let loader = match config.format {
+    ModuleFormat::Async => r#"
+function load(id, importers) {
+  if (importers.includes(id)) {
+    return Promise.resolve(modules[id].exports);
+  }
+  if (!modules[id]) {
+    const exports = {};
+    const exportsAll = (mod) => {
+      for (const key of Object.keys(mod)) {
+        if (key !== 'default' && !(key in exports)) {
+          Object.defineProperty(exports, key, { enumerable: true, get: () => mod[key] });
+        }
+      }
+    };
+    const importModule = (dep) => load(dep, [...importers, id]);
+    modules[id] = { exports };
+    modules[id].promise = factories[id](importModule, exports, exportsAll).then(() => exports);
+  }
+  return modules[id].promise;
+}
+"#,
+    ModuleFormat::CommonJs => r#"
+function load(id) {
+  if (!modules[id]) {
+    modules[id] = { exports: {} };
+    factories[id](load, modules[id]);
+  }
+  return modules[id].exports;
+}
+"#,
+  };
+  let script = format!(r#"
+const log = [];
+const factories = {{
+{factories}
+}};
+const modules = {{}};
+{loader}
+Promise.resolve(load('./input', [])).then((exports) => {{
+  console.log(JSON.stringify({{ log, exports }}));
+}});
+"#);
+  let output = Command::new("node").arg("-e").arg(&script).output().expect("failed to run node");
+  assert!(output.status.success(), "{}\n{}", script, String::from_utf8_lossy(&output.stderr));
+  String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn parallel_imports_runtime() {
+  let dir = Path::new("tests/fixture/parallel-imports");
+  let sequential = run_module_graph(dir, Default::default());
+  let parallel = run_module_graph(dir, NodeTransformConfig {
+    parallel_imports: true,
+    ..Default::default()
+  });
+  assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn cycle_runtime() {
+  let output = run_module_graph(Path::new("tests/fixture/cycle"), Default::default());
+  assert_eq!(
+    output.trim(),
+    r#"{"log":["b: function","input: a,Klass,value"],"exports":{"value":"a"}}"#
+  );
+}
+
+// Imported functions are called with an undefined `this`, like ESM bindings.
+#[test]
+fn live_bindings_call_this_runtime() {
+  let output = run_module_graph(Path::new("tests/fixture/live-bindings/call-this"), NodeTransformConfig {
+    live_bindings: true,
+    ..Default::default()
+  });
+  assert_eq!(output.trim(), r#"{"log":[true,true],"exports":{}}"#);
+}
+
+#[test]
+fn ts_export_assignment_runtime() {
+  let dir = Path::new("tests/fixture/ts-export-assignment");
+  let output = run_module_graph(dir, Default::default());
+  assert_eq!(output.trim(), r#"{"log":["hello ts"],"exports":{}}"#);
+  let commonjs = run_module_graph(dir, NodeTransformConfig {
+    format: ModuleFormat::CommonJs,
+    ..Default::default()
+  });
+  assert_eq!(output, commonjs);
+}
+
+#[test]
+fn commonjs_runtime() {
+  for dir in ["tests/fixture/parallel-imports", "tests/fixture/cycle"] {
+    let dir = Path::new(dir);
+    let commonjs = run_module_graph(dir, NodeTransformConfig {
+      format: ModuleFormat::CommonJs,
+      ..Default::default()
+    });
+    assert_eq!(run_module_graph(dir, Default::default()), commonjs);
+  }
+}
+
+// Load a real CommonJS module of node, whose `module.exports` is the default export.
+#[test]
+fn commonjs_interop_runtime() {
+  let src = "import path, { join } from 'path';\nexport default path.join('a', join('b', 'c'));";
+  let code = transform_module(NodeTransformConfig {
+    format: ModuleFormat::CommonJs,
+    ..Default::default()
+  }, src);
+  let script = format!(
+    "const module = {{ exports: {{}} }};\n((require, module) => {{\n{}\n}})(require, module);\nconsole.log(module.exports.default, module.exports.__esModule);",
+    code,
+  );
+  let output = Command::new("node").arg("-e").arg(&script).output().expect("failed to run node");
+  assert!(output.status.success(), "{}\n{}", script, String::from_utf8_lossy(&output.stderr));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "a/b/c true\n");
+}
+
+#[test]
+fn spans() {
+  let src = fs::read_to_string("tests/fixture/spans/input.js").unwrap();
+  Tester::run(|tester| {
+    let program = tester.apply_transform(node_transform(Default::default()), "input.js", Default::default(), Some(true), &src)?;
+    let module = program.expect_module();
+    let line = |span: Span| tester.cm.lookup_char_pos(span.lo).line;
+
+    // Every generated statement points at the import or export it was created from.
+    let lines: Vec<usize> = module.body.iter().map(|item| line(item.span())).collect();
+    assert_eq!(lines, vec![5, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 6, 7]);
+
+    // The bindings keep the spans of the original identifiers.
+    let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) = &module.body[2] else { panic!("expected `const a`") };
+    let Pat::Ident(binding) = &var.decls[0].name else { panic!("expected a binding identifier") };
+    assert_eq!(tester.cm.span_to_snippet(binding.span).unwrap(), "a");
+
+    // The dynamic import helper replaces the `import` keyword.
+    let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &module.body[12] else { panic!("expected `import('f')`") };
+    let Expr::Call(CallExpr { callee: Callee::Expr(callee), .. }) = &**expr else { panic!("expected a call") };
+    assert_eq!(tester.cm.span_to_snippet(callee.span()).unwrap(), "import");
+    Ok(())
+  });
+}
+
+#[test]
+fn import_var_without_placeholder() {
+  let output = transform_module(
+    NodeTransformConfig {
+      helpers: RuntimeHelpers {
+        import_var: String::from("__imp__"),
+        ..Default::default()
+      },
+      ..Default::default()
+    },
+    "import a from 'a';\nimport b from 'b';\nconsole.log(a, b);",
+  );
+  assert!(output.contains("const __imp__0 = await __ice_import__(\"a\");"), "{}", output);
+  assert!(output.contains("const __imp__1 = await __ice_import__(\"b\");"), "{}", output);
+}
+
+#[test]
+fn create_outside_globals() {
+  // Creating the transform does not need `GLOBALS`, only running it does.
+  let _ = node_transform(Default::default());
+}
@@ -1,8 +1,8 @@
-class foo {
-}
 Object.defineProperty(__ice_exports__, "foo", {
     enumerable: true,
     get  () {
         return foo;
     }
 });
+class foo {
+}
@@ -0,0 +1,9 @@
+import foo, { bar } from 'foo';
+import * as ns from 'ns';
+export * from 'all';
+export { baz } from 'baz';
+export const value = [foo, bar, ns];
+export default function load() {
+  return [import('lazy'), import.meta.url];
+}
+console.log(import.meta, import.meta.dirname);
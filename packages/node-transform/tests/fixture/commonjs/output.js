@@ -0,0 +1,53 @@
+Object.defineProperty(module.exports, "__esModule", {
+    value: true
+});
+Object.defineProperty(module.exports, "value", {
+    enumerable: true,
+    get  () {
+        return value;
+    }
+});
+Object.defineProperty(module.exports, "default", {
+    enumerable: true,
+    get  () {
+        return load;
+    }
+});
+const __ice_import_0__ = require("foo");
+const foo = __ice_import_0__ && __ice_import_0__.__esModule ? __ice_import_0__.default : __ice_import_0__;
+const bar = __ice_import_0__.bar;
+const __ice_import_1__ = require("ns");
+const ns = __ice_import_1__;
+const __ice_import_2__ = require("all");
+Object.keys(__ice_import_2__).forEach(function(key) {
+    if (key === "default" || Object.prototype.hasOwnProperty.call(module.exports, key)) return;
+    Object.defineProperty(module.exports, key, {
+        enumerable: true,
+        get  () {
+            return __ice_import_2__[key];
+        }
+    });
+});
+const __ice_import_3__ = require("baz");
+Object.defineProperty(module.exports, "baz", {
+    enumerable: true,
+    get  () {
+        return __ice_import_3__.baz;
+    }
+});
+const value = [
+    foo,
+    bar,
+    ns
+];
+function load() {
+    return [
+        Promise.resolve('lazy').then(require),
+        require("url").pathToFileURL(__filename).href
+    ];
+}
+console.log({
+    url: require("url").pathToFileURL(__filename).href,
+    filename: __filename,
+    dirname: __dirname
+}, __dirname);
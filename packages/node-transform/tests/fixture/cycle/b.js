@@ -0,0 +1,8 @@
+import * as input from './input';
+import { a } from './input';
+
+log.push('b: ' + typeof a);
+
+export function describe() {
+  return 'input: ' + Object.keys(input).join(',');
+}
@@ -0,0 +1,9 @@
+import { describe } from './b';
+
+log.push(describe());
+
+export function a() {
+  return 'a';
+}
+export class Klass {}
+export const value = a();
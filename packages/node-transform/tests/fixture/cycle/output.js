@@ -0,0 +1,27 @@
+Object.defineProperty(__ice_exports__, "a", {
+    enumerable: true,
+    get  () {
+        return a;
+    }
+});
+Object.defineProperty(__ice_exports__, "Klass", {
+    enumerable: true,
+    get  () {
+        return Klass;
+    }
+});
+Object.defineProperty(__ice_exports__, "value", {
+    enumerable: true,
+    get  () {
+        return value;
+    }
+});
+const __ice_import_0__ = await __ice_import__("./b");
+const describe = __ice_import_0__.describe;
+log.push(describe());
+function a() {
+    return 'a';
+}
+class Klass {
+}
+const value = a();
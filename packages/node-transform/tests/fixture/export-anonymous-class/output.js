@@ -0,0 +1,13 @@
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return _default;
+    }
+});
+class _default {
+    static create() {}
+}
+Object.defineProperty(_default, "name", {
+    value: "default",
+    configurable: true
+});
@@ -0,0 +1,11 @@
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return _default;
+    }
+});
+const _default = function() {};
+Object.defineProperty(_default, "name", {
+    value: "default",
+    configurable: true
+});
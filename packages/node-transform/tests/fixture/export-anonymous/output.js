@@ -1 +1,11 @@
-__ice_exports__.default = function() {}
\ No newline at end of file
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return _default;
+    }
+});
+Object.defineProperty(_default, "name", {
+    value: "default",
+    configurable: true
+});
+function _default() {}
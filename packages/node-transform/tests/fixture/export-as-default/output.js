@@ -0,0 +1,7 @@
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return x;
+    }
+});
+const x = 1;
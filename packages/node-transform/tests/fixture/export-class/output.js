@@ -1,8 +1,8 @@
-class a {
-}
 Object.defineProperty(__ice_exports__, "default", {
     enumerable: true,
     get  () {
         return a;
     }
 });
+class a {
+}
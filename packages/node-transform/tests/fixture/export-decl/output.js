@@ -1,7 +1,7 @@
-function a() {}
 Object.defineProperty(__ice_exports__, "default", {
     enumerable: true,
     get  () {
         return a;
     }
 });
+function a() {}
@@ -0,0 +1,2 @@
+export { default } from 'y';
+export { default as z } from 'z';
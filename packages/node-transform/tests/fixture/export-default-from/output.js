@@ -0,0 +1,14 @@
+const __ice_import_0__ = await __ice_import__("y");
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return __ice_import_0__.default;
+    }
+});
+const __ice_import_1__ = await __ice_import__("z");
+Object.defineProperty(__ice_exports__, "z", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__.default;
+    }
+});
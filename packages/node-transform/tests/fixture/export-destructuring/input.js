@@ -0,0 +1,2 @@
+export const { a, b: { c }, ...rest } = obj;
+export let [x, , y = 1, ...others] = arr;
@@ -0,0 +1,38 @@
+Object.defineProperty(__ice_exports__, "a", {
+    enumerable: true,
+    get  () {
+        return a;
+    }
+});
+Object.defineProperty(__ice_exports__, "c", {
+    enumerable: true,
+    get  () {
+        return c;
+    }
+});
+Object.defineProperty(__ice_exports__, "rest", {
+    enumerable: true,
+    get  () {
+        return rest;
+    }
+});
+Object.defineProperty(__ice_exports__, "x", {
+    enumerable: true,
+    get  () {
+        return x;
+    }
+});
+Object.defineProperty(__ice_exports__, "y", {
+    enumerable: true,
+    get  () {
+        return y;
+    }
+});
+Object.defineProperty(__ice_exports__, "others", {
+    enumerable: true,
+    get  () {
+        return others;
+    }
+});
+const { a, b: { c }, ...rest } = obj;
+let [x, , y = 1, ...others] = arr;
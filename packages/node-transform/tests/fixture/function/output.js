@@ -1,7 +1,7 @@
-function foo() {}
 Object.defineProperty(__ice_exports__, "foo", {
     enumerable: true,
     get  () {
         return foo;
     }
 });
+function foo() {}
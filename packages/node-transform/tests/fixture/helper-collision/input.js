@@ -0,0 +1,11 @@
+import { a } from 'a';
+const __ice_import_0__ = 'local';
+function __ice_exports__() {}
+let __ice_import__ = 1;
+export const value = [a, __ice_import_0__, __ice_exports__(), __ice_import__];
+export default function load() {
+  const __ice_exports_all__ = 2;
+  const __ice_import_meta__ = 3;
+  return [import('b'), import.meta.url, __ice_exports_all__, __ice_import_meta__];
+}
+export * from 'c';
@@ -0,0 +1,35 @@
+Object.defineProperty(__ice_exports__, "value", {
+    enumerable: true,
+    get  () {
+        return value;
+    }
+});
+Object.defineProperty(__ice_exports__, "default", {
+    enumerable: true,
+    get  () {
+        return load;
+    }
+});
+const __ice_import_0__ = await __ice_import__("a");
+const a = __ice_import_0__.a;
+const __ice_import_0__1 = 'local';
+function __ice_exports__1() {}
+let __ice_import__1 = 1;
+const value = [
+    a,
+    __ice_import_0__1,
+    __ice_exports__1(),
+    __ice_import__1
+];
+function load() {
+    const __ice_exports_all__1 = 2;
+    const __ice_import_meta__1 = 3;
+    return [
+        __ice_dynamic_import__('b'),
+        __ice_import_meta__.url,
+        __ice_exports_all__1,
+        __ice_import_meta__1
+    ];
+}
+const __ice_import_1__ = await __ice_import__("c");
+__ice_exports_all__(__ice_import_1__);
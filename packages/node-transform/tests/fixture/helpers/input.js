@@ -0,0 +1,6 @@
+import foo, { bar } from 'foo';
+export * from 'baz';
+export { bar };
+export default foo;
+const lazy = () => import('lazy');
+console.log(import.meta.url, lazy);
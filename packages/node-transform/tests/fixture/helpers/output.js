@@ -0,0 +1,14 @@
+Object.defineProperty(__vite_ssr_exports__, "bar", {
+    enumerable: true,
+    get  () {
+        return bar;
+    }
+});
+const __vite_ssr_import_0__ = await __vite_ssr_import__("foo");
+const foo = __vite_ssr_import_0__.default;
+const bar = __vite_ssr_import_0__.bar;
+const __vite_ssr_import_1__ = await __vite_ssr_import__("baz");
+__vite_ssr_exportAll__(__vite_ssr_import_1__);
+__vite_ssr_exports__.default = foo;
+const lazy = ()=>__vite_ssr_dynamic_import__('lazy');
+console.log(__vite_ssr_import_meta__.url, lazy);
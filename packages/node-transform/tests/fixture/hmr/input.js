@@ -0,0 +1,16 @@
+import React, { useState, useEffect } from 'react';
+import * as utils from './utils';
+import * as icons from './icons';
+import { Layout } from './layout';
+export { routes } from './routes';
+export * from './config';
+
+export function App() {
+  const [state] = useState(utils.initialState);
+  return <Layout icons={icons}><icons.Logo /></Layout>;
+}
+
+if (import.meta.hot) {
+  import.meta.hot.accept();
+  import.meta.hot?.dispose(() => React.unmount());
+}
@@ -0,0 +1,33 @@
+/*__ice_module_info__ {"exports":["routes","App"],"exportAll":["./config"],"imports":[{"source":"react","specifiers":["default","useState","useEffect"],"used":["useState","default"]},{"source":"./utils","specifiers":["*"],"used":["initialState"]},{"source":"./icons","specifiers":["*"],"used":["*","Logo"]},{"source":"./layout","specifiers":["Layout"],"used":["Layout"]},{"source":"./routes","specifiers":["routes"],"used":["routes"]},{"source":"./config","specifiers":[],"used":["*"]}]}*/ Object.defineProperty(__ice_exports__, "App", {
+    enumerable: true,
+    get  () {
+        return App;
+    }
+});
+const __ice_import_0__ = await __ice_import__("react");
+const React = __ice_import_0__.default;
+const useState = __ice_import_0__.useState;
+const useEffect = __ice_import_0__.useEffect;
+const __ice_import_1__ = await __ice_import__("./utils");
+const utils = __ice_import_1__;
+const __ice_import_2__ = await __ice_import__("./icons");
+const icons = __ice_import_2__;
+const __ice_import_3__ = await __ice_import__("./layout");
+const Layout = __ice_import_3__.Layout;
+const __ice_import_4__ = await __ice_import__("./routes");
+Object.defineProperty(__ice_exports__, "routes", {
+    enumerable: true,
+    get  () {
+        return __ice_import_4__.routes;
+    }
+});
+const __ice_import_5__ = await __ice_import__("./config");
+__ice_exports_all__(__ice_import_5__);
+function App() {
+    const [state] = useState(utils.initialState);
+    return <Layout icons={icons}><icons.Logo/></Layout>;
+}
+if (__ice_hot__) {
+    __ice_hot__.accept();
+    __ice_hot__?.dispose(()=>React.unmount());
+}
@@ -0,0 +1,6 @@
+import data from './data.json' with { type: 'json' };
+import { load } from './data.json';
+import raw from './data.json' with { type: 'json' };
+export { default as config } from './data.json' with { type: 'json' };
+console.log(data, raw, load);
+const lazy = import('./lazy.json', { with: { type: 'json' } });
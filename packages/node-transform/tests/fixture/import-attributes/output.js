@@ -0,0 +1,19 @@
+const __ice_import_0__ = await __ice_import__("./data.json", {
+    type: 'json'
+});
+const data = __ice_import_0__.default;
+const __ice_import_1__ = await __ice_import__("./data.json");
+const load = __ice_import_1__.load;
+const raw = __ice_import_0__.default;
+Object.defineProperty(__ice_exports__, "config", {
+    enumerable: true,
+    get  () {
+        return __ice_import_0__.default;
+    }
+});
+console.log(data, raw, load);
+const lazy = __ice_dynamic_import__('./lazy.json', {
+    with: {
+        type: 'json'
+    }
+});
@@ -0,0 +1,2 @@
+console.log(import.meta.url, import.meta.dirname, import.meta.filename);
+console.log(import.meta.env, import.meta["url"]);
@@ -0,0 +1,2 @@
+console.log("file:///project/src/pages/about%20us.js", "/project/src/pages", "/project/src/pages/about us.js");
+console.log(__ice_import_meta__.env, __ice_import_meta__["url"]);
@@ -0,0 +1,3 @@
+import { getThis, tag } from './lib';
+
+log.push(getThis() === undefined, tag`template` === undefined);
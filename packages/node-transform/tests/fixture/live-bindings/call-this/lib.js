@@ -0,0 +1,9 @@
+export function getThis() {
+  'use strict';
+  return this;
+}
+
+export function tag() {
+  'use strict';
+  return this;
+}
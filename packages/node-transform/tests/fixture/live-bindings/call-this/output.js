@@ -0,0 +1,2 @@
+const __ice_import_0__ = await __ice_import__("./lib");
+log.push((0, __ice_import_0__.getThis)() === undefined, (0, __ice_import_0__.tag)`template` === undefined);
@@ -0,0 +1,17 @@
+import { count, increment } from './counter';
+import foo, { bar as baz } from 'foo';
+import * as ns from 'ns';
+
+increment();
+baz`tagged ${count}`;
+console.log(count, baz, foo, ns.value);
+
+function shadow(count) {
+  const baz = 1;
+  return count + baz;
+}
+
+export const state = { count, foo };
+export { count, baz as renamed };
+export default typeof count;
+export const view = <foo.Item><baz.List.Row /></foo.Item>;
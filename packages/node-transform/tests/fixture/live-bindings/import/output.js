@@ -0,0 +1,41 @@
+Object.defineProperty(__ice_exports__, "state", {
+    enumerable: true,
+    get  () {
+        return state;
+    }
+});
+Object.defineProperty(__ice_exports__, "count", {
+    enumerable: true,
+    get  () {
+        return __ice_import_0__.count;
+    }
+});
+Object.defineProperty(__ice_exports__, "renamed", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__.bar;
+    }
+});
+Object.defineProperty(__ice_exports__, "view", {
+    enumerable: true,
+    get  () {
+        return view;
+    }
+});
+const __ice_import_0__ = await __ice_import__("./counter");
+const __ice_import_1__ = await __ice_import__("foo");
+const __ice_import_2__ = await __ice_import__("ns");
+const ns = __ice_import_2__;
+(0, __ice_import_0__.increment)();
+(0, __ice_import_1__.bar)`tagged ${__ice_import_0__.count}`;
+console.log(__ice_import_0__.count, __ice_import_1__.bar, __ice_import_1__.default, ns.value);
+function shadow(count) {
+    const baz = 1;
+    return count + baz;
+}
+const state = {
+    count: __ice_import_0__.count,
+    foo: __ice_import_1__.default
+};
+__ice_exports__.default = typeof __ice_import_0__.count;
+const view = <__ice_import_1__.default.Item><__ice_import_1__.bar.List.Row/></__ice_import_1__.default.Item>;
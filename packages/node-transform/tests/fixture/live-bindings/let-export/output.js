@@ -0,0 +1,16 @@
+Object.defineProperty(__ice_exports__, "count", {
+    enumerable: true,
+    get  () {
+        return count;
+    }
+});
+Object.defineProperty(__ice_exports__, "increment", {
+    enumerable: true,
+    get  () {
+        return increment;
+    }
+});
+let count = 0;
+function increment() {
+    count += 1;
+}
@@ -0,0 +1,3 @@
+const meta = import.meta;
+log(import.meta);
+console.log(import.meta?.url, import.meta[key], import.meta.env.MODE);
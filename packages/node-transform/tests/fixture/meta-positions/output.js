@@ -0,0 +1,3 @@
+const meta = __ice_import_meta__;
+log(__ice_import_meta__);
+console.log(__ice_import_meta__?.url, __ice_import_meta__[key], __ice_import_meta__.env.MODE);
@@ -1,4 +1,3 @@
 const __ice_import_0__ = await __ice_import__("test");
 __ice_exports_all__(__ice_import_0__);
-const __ice_import_1__ = await __ice_import__("test");
-const a = __ice_import_1__.a;
\ No newline at end of file
+const a = __ice_import_0__.a;
@@ -0,0 +1,2 @@
+import { value } from './a*/b';
+export { value as "*/ end" };
@@ -0,0 +1,8 @@
+/*__ice_module_info__ {"exports":["*\u002f end"],"exportAll":[],"imports":[{"source":"./a*\u002fb","specifiers":["value"]}]}*/ Object.defineProperty(__ice_exports__, "*/ end", {
+    enumerable: true,
+    get  () {
+        return value;
+    }
+});
+const __ice_import_0__ = await __ice_import__("./a*/b");
+const value = __ice_import_0__.value;
@@ -0,0 +1,11 @@
+import React, { useState as useReactState } from 'react';
+import * as path from 'path';
+import './polyfill';
+export { join, default as resolve } from 'path';
+export * from './utils';
+export * as constants from './constants';
+export const { a, b: [c] } = useReactState();
+export function render() {}
+const local = React;
+export { local as "local-name" };
+export default path;
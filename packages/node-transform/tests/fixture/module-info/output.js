@@ -0,0 +1,55 @@
+/*__ice_module_info__ {"exports":["join","resolve","constants","a","c","render","local-name","default"],"exportAll":["./utils"],"imports":[{"source":"react","specifiers":["default","useState"]},{"source":"path","specifiers":["*","join","default"]},{"source":"./polyfill","specifiers":[]},{"source":"./utils","specifiers":[]},{"source":"./constants","specifiers":["*"]}]}*/ Object.defineProperty(__ice_exports__, "a", {
+    enumerable: true,
+    get  () {
+        return a;
+    }
+});
+Object.defineProperty(__ice_exports__, "c", {
+    enumerable: true,
+    get  () {
+        return c;
+    }
+});
+Object.defineProperty(__ice_exports__, "render", {
+    enumerable: true,
+    get  () {
+        return render;
+    }
+});
+Object.defineProperty(__ice_exports__, "local-name", {
+    enumerable: true,
+    get  () {
+        return local;
+    }
+});
+const __ice_import_0__ = await __ice_import__("react");
+const React = __ice_import_0__.default;
+const useReactState = __ice_import_0__.useState;
+const __ice_import_1__ = await __ice_import__("path");
+const path = __ice_import_1__;
+const __ice_import_2__ = await __ice_import__("./polyfill");
+Object.defineProperty(__ice_exports__, "join", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__.join;
+    }
+});
+Object.defineProperty(__ice_exports__, "resolve", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__.default;
+    }
+});
+const __ice_import_3__ = await __ice_import__("./utils");
+__ice_exports_all__(__ice_import_3__);
+const __ice_import_4__ = await __ice_import__("./constants");
+Object.defineProperty(__ice_exports__, "constants", {
+    enumerable: true,
+    get  () {
+        return __ice_import_4__;
+    }
+});
+const { a, b: [c] } = useReactState();
+function render() {}
+const local = React;
+__ice_exports__.default = path;
@@ -1,8 +1,8 @@
-const __ice_import_0__ = await __ice_import__("test");
-const a = __ice_import_0__.a;
 Object.defineProperty(__ice_exports__, "a", {
     enumerable: true,
     get  () {
         return a;
     }
 });
+const __ice_import_0__ = await __ice_import__("test");
+const a = __ice_import_0__.a;
@@ -1,4 +1,3 @@
-const a = 1, b = 2;
 Object.defineProperty(__ice_exports__, "a", {
     enumerable: true,
     get  () {
@@ -11,3 +10,4 @@ Object.defineProperty(__ice_exports__, "c", {
         return b;
     }
 });
+const a = 1, b = 2;
@@ -0,0 +1,2 @@
+log.push('b');
+export const b = globalThis.polyfilled ? 'b' : 'missing polyfill';
@@ -0,0 +1,4 @@
+import { a } from './a';
+log.push('c');
+export const cc = a + 'c';
+export default 'c';
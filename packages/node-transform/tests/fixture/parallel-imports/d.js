@@ -0,0 +1,5 @@
+log.push('d');
+export let d = 0;
+export function increment() {
+  d += 1;
+}
@@ -0,0 +1,9 @@
+import { a } from './a';
+import c from './c';
+import './polyfill';
+import { b } from './b';
+export { d } from './d';
+export * from './c';
+
+log.push('input');
+export const result = [a, b, c, globalThis.polyfilled];
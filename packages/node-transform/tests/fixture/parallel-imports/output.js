@@ -0,0 +1,32 @@
+Object.defineProperty(__ice_exports__, "result", {
+    enumerable: true,
+    get  () {
+        return result;
+    }
+});
+const [__ice_import_0__, __ice_import_1__] = await Promise.all([
+    __ice_import__("./a"),
+    __ice_import__("./c")
+]);
+const __ice_import_2__ = await __ice_import__("./polyfill");
+const [__ice_import_3__, __ice_import_4__] = await Promise.all([
+    __ice_import__("./b"),
+    __ice_import__("./d")
+]);
+const a = __ice_import_0__.a;
+const c = __ice_import_1__.default;
+const b = __ice_import_3__.b;
+Object.defineProperty(__ice_exports__, "d", {
+    enumerable: true,
+    get  () {
+        return __ice_import_4__.d;
+    }
+});
+__ice_exports_all__(__ice_import_1__);
+log.push('input');
+const result = [
+    a,
+    b,
+    c,
+    globalThis.polyfilled
+];
@@ -0,0 +1,3 @@
+import { value } from './value';
+log.push('polyfill');
+globalThis.polyfilled = value;
@@ -0,0 +1,2 @@
+log.push('value');
+export const value = true;
@@ -0,0 +1,7 @@
+import a, { b } from 'a';
+import * as ns from 'ns';
+export { c } from 'c';
+export * from 'd';
+export const e = ns;
+export default a + b;
+import('f');
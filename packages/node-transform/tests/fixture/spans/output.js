@@ -0,0 +1,23 @@
+Object.defineProperty(__ice_exports__, "e", {
+    enumerable: true,
+    get  () {
+        return e;
+    }
+});
+const __ice_import_0__ = await __ice_import__("a");
+const a = __ice_import_0__.default;
+const b = __ice_import_0__.b;
+const __ice_import_1__ = await __ice_import__("ns");
+const ns = __ice_import_1__;
+const __ice_import_2__ = await __ice_import__("c");
+Object.defineProperty(__ice_exports__, "c", {
+    enumerable: true,
+    get  () {
+        return __ice_import_2__.c;
+    }
+});
+const __ice_import_3__ = await __ice_import__("d");
+__ice_exports_all__(__ice_import_3__);
+const e = ns;
+__ice_exports__.default = a + b;
+__ice_dynamic_import__('f');
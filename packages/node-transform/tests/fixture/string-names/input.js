@@ -0,0 +1,6 @@
+import { "foo-bar" as fb, "default" as def, "valid" as v } from 'x';
+export { "baz-qux" as bq, "a b" as "c d", plain as "with space" } from 'y';
+export * as "ns-name" from 'z';
+const local = 1;
+export { local as "local-name" };
+console.log(fb, def, v);
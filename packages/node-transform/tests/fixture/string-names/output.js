@@ -0,0 +1,38 @@
+Object.defineProperty(__ice_exports__, "local-name", {
+    enumerable: true,
+    get  () {
+        return local;
+    }
+});
+const __ice_import_0__ = await __ice_import__("x");
+const fb = __ice_import_0__["foo-bar"];
+const def = __ice_import_0__.default;
+const v = __ice_import_0__.valid;
+const __ice_import_1__ = await __ice_import__("y");
+Object.defineProperty(__ice_exports__, "bq", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__["baz-qux"];
+    }
+});
+Object.defineProperty(__ice_exports__, "c d", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__["a b"];
+    }
+});
+Object.defineProperty(__ice_exports__, "with space", {
+    enumerable: true,
+    get  () {
+        return __ice_import_1__.plain;
+    }
+});
+const __ice_import_2__ = await __ice_import__("z");
+Object.defineProperty(__ice_exports__, "ns-name", {
+    enumerable: true,
+    get  () {
+        return __ice_import_2__;
+    }
+});
+const local = 1;
+console.log(fb, def, v);
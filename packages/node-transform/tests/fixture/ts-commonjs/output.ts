@@ -0,0 +1,3 @@
+const __ice_import_0__ = require("fs");
+const fs = __ice_import_0__;
+module.exports = fs;
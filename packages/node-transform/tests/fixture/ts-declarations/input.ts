@@ -0,0 +1,22 @@
+export enum Color {
+  Red,
+  Green,
+}
+export namespace Utils {
+  export const name = 'utils';
+}
+export declare const env: string;
+export declare function log(message: string): void;
+export interface Options {
+  name: string;
+}
+export type Name = string;
+export namespace Types {
+  export interface Item {
+    id: number;
+  }
+  export type Id = Item['id'];
+}
+export namespace Nested.Values {
+  export const value = 1;
+}
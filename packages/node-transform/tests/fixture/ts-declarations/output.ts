@@ -0,0 +1,40 @@
+Object.defineProperty(__ice_exports__, "Color", {
+    enumerable: true,
+    get  () {
+        return Color;
+    }
+});
+Object.defineProperty(__ice_exports__, "Utils", {
+    enumerable: true,
+    get  () {
+        return Utils;
+    }
+});
+Object.defineProperty(__ice_exports__, "Nested", {
+    enumerable: true,
+    get  () {
+        return Nested;
+    }
+});
+enum Color {
+    Red,
+    Green
+}
+namespace Utils {
+    export const name = 'utils';
+}
+declare const env: string;
+declare function log(message: string): void;
+interface Options {
+    name: string;
+}
+type Name = string;
+namespace Types {
+    export interface Item {
+        id: number;
+    }
+    export type Id = Item['id'];
+}
+namespace Nested.Values {
+    export const value = 1;
+}
@@ -0,0 +1,4 @@
+export default interface Props {
+  name: string;
+}
+export const kind = 'props';
@@ -0,0 +1,7 @@
+Object.defineProperty(__ice_exports__, "kind", {
+    enumerable: true,
+    get  () {
+        return kind;
+    }
+});
+const kind = 'props';
@@ -0,0 +1,3 @@
+import lib = require('./lib');
+
+log.push(lib.greet('ts'));
@@ -0,0 +1,5 @@
+function greet(name) {
+  return 'hello ' + name;
+}
+
+export = { greet };
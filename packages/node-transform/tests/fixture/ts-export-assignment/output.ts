@@ -0,0 +1,3 @@
+const __ice_import_0__ = await __ice_import__("./lib");
+const lib = __ice_import_0__.default;
+log.push(lib.greet('ts'));
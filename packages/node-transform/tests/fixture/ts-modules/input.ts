@@ -0,0 +1,11 @@
+import type { Options } from './types';
+import { type Name, format } from './format';
+import fs = require('fs');
+import type path = require('path');
+export import os = require('os');
+import Color = Utils.Color;
+export type { Options };
+export { type Name, format };
+export type { Config } from './config';
+export as namespace Lib;
+export = fs.readFileSync(format(os.EOL, Color));
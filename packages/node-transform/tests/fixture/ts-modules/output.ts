@@ -0,0 +1,20 @@
+Object.defineProperty(__ice_exports__, "os", {
+    enumerable: true,
+    get  () {
+        return os;
+    }
+});
+Object.defineProperty(__ice_exports__, "format", {
+    enumerable: true,
+    get  () {
+        return format;
+    }
+});
+const __ice_import_0__ = await __ice_import__("./format");
+const format = __ice_import_0__.format;
+const __ice_import_1__ = await __ice_import__("fs");
+const fs = __ice_import_1__.default;
+const __ice_import_2__ = await __ice_import__("os");
+const os = __ice_import_2__.default;
+import Color = Utils.Color;
+__ice_exports__.default = fs.readFileSync(format(os.EOL, Color));
@@ -1,4 +1,3 @@
-const a = 1, b = 2;
 Object.defineProperty(__ice_exports__, "a", {
     enumerable: true,
     get  () {
@@ -11,10 +10,11 @@ Object.defineProperty(__ice_exports__, "b", {
         return b;
     }
 });
-const c = 3;
 Object.defineProperty(__ice_exports__, "c", {
     enumerable: true,
     get  () {
         return c;
     }
 });
+const a = 1, b = 2;
+const c = 3;
@@ -85,7 +85,7 @@ impl Analyzer<'_> {
             return e
         }
 
-        return e.fold_children_with(self);
+        e.fold_children_with(self)
     }
 }
 
@@ -142,7 +142,7 @@ impl Fold for Analyzer<'_> {
 
         self.in_data_fn = old_in_data;
 
-        return e;
+        e
     }
 
     fn fold_expr(&mut self, e: Expr) -> Expr {
@@ -248,11 +248,11 @@ impl Fold for Analyzer<'_> {
     }
 
     fn fold_default_decl(&mut self, d: DefaultDecl) -> DefaultDecl {
-        return self.check_default(d);
+        self.check_default(d)
     }
 
     fn fold_export_default_expr(&mut self, e: ExportDefaultExpr) -> ExportDefaultExpr {
-        return self.check_default(e);
+        self.check_default(e)
     }
 
     fn fold_prop(&mut self, p: Prop) -> Prop {
@@ -311,7 +311,7 @@ impl RemoveExportsExprs {
     }
 
     fn create_empty_fn(&mut self) -> FnExpr {
-        return FnExpr {
+        FnExpr {
             ident: None,
             function: Box::new(Function {
                 ctxt: SyntaxContext::empty(),
@@ -328,7 +328,7 @@ impl RemoveExportsExprs {
                 return_type: None,
                 type_params: None,
             })
-        };
+        }
     }
 }
 
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 use swc_core::{
-  ecma::parser::{EsConfig, Syntax},
+  ecma::parser::{EsSyntax, Syntax},
   ecma::transforms::testing::{test_fixture, FixtureTestConfig},
 };
 use testing::fixture;
@@ -30,7 +30,7 @@ fn fixture_preserve_config(input: PathBuf) {
   let output = parent.join("output.js");
 
   test_fixture(
-    Syntax::Es(EsConfig {
+    Syntax::Es(EsSyntax {
       decorators: true,
       jsx: true,
       ..Default::default()
@@ -52,7 +52,7 @@ fn fixture_preserve_config_and_config(input: PathBuf) {
   let output = parent.join("output.js");
 
   test_fixture(
-    Syntax::Es(EsConfig {
+    Syntax::Es(EsSyntax {
       decorators: true,
       jsx: true,
       ..Default::default()
@@ -74,7 +74,7 @@ fn fixture_preserve_data(input: PathBuf) {
   let output = parent.join("output.js");
 
   test_fixture(
-    Syntax::Es(EsConfig {
+    Syntax::Es(EsSyntax {
       decorators: true,
       jsx: true,
       ..Default::default()
@@ -96,7 +96,7 @@ fn fixture_preserve_default(input: PathBuf) {
   let output = parent.join("output.js");
 
   test_fixture(
-    Syntax::Es(EsConfig {
+    Syntax::Es(EsSyntax {
       decorators: true,
       jsx: true,
       ..Default::default()
@@ -118,7 +118,7 @@ fn fixture_remove_data(input: PathBuf) {
   let output = parent.join("output.js");
 
   test_fixture(
-    Syntax::Es(EsConfig {
+    Syntax::Es(EsSyntax {
       decorators: true,
       jsx: true,
       ..Default::default()